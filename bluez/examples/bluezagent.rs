@@ -76,6 +76,10 @@ async fn main() -> eyre::Result<()> {
 				_ = response.send(true);
 			}
 
+			bluez_zbus::agent1::Message::AuthorizeService { response, .. } => {
+				_ = response.send(true);
+			}
+
 			bluez_zbus::agent1::Message::RequestPasskey { device, response } => {
 				_ = response.send(None);
 			}
@@ -0,0 +1,315 @@
+// Copyright 2024 System76 <info@system76.com>
+// SPDX-License-Identifier: MPL-2.0
+
+//! Decoding of advertised manufacturer and service data.
+
+use std::collections::HashMap;
+
+use zbus::zvariant::OwnedValue;
+
+use crate::{category::DeviceClass, uuid::BluetoothUuid};
+
+pub const COMPANY_MICROSOFT: u16 = 0x0006;
+pub const COMPANY_APPLE: u16 = 0x004c;
+
+/// The 16-bit service UUID Eddystone frames are advertised under.
+pub const EDDYSTONE_SERVICE: u16 = 0xfeaa;
+
+/// Decodes the `ManufacturerData` property into the data of each company identifier.
+pub fn manufacturer_data(raw: HashMap<u16, OwnedValue>) -> HashMap<u16, Vec<u8>> {
+	raw.into_iter()
+		.filter_map(|(company, value)| Some((company, value.try_into().ok()?)))
+		.collect()
+}
+
+/// Decodes the `ServiceData` property into the data of each service UUID.
+pub fn service_data(raw: HashMap<String, OwnedValue>) -> HashMap<BluetoothUuid, Vec<u8>> {
+	raw.into_iter()
+		.filter_map(|(uuid, value)| Some((uuid.parse().ok()?, value.try_into().ok()?)))
+		.collect()
+}
+
+/// Decodes the `AdvertisingData` property into the data of each AD type.
+pub fn advertising_data(raw: HashMap<u8, OwnedValue>) -> HashMap<u8, Vec<u8>> {
+	raw.into_iter()
+		.filter_map(|(ad_type, value)| Some((ad_type, value.try_into().ok()?)))
+		.collect()
+}
+
+/// The name of a company identifier from the Bluetooth assigned numbers, for
+/// the companies most commonly seen in advertisements.
+pub fn company_name(company: u16) -> Option<&'static str> {
+	let name = match company {
+		0x0000 => "Ericsson",
+		0x0001 => "Nokia",
+		0x0002 => "Intel",
+		0x0003 => "IBM",
+		0x0006 => "Microsoft",
+		0x000a => "Qualcomm Technologies International",
+		0x000d => "Texas Instruments",
+		0x000f => "Broadcom",
+		0x001d => "Qualcomm",
+		0x0046 => "MediaTek",
+		0x004c => "Apple",
+		0x0059 => "Nordic Semiconductor",
+		0x005d => "Realtek",
+		0x0075 => "Samsung Electronics",
+		0x0087 => "Garmin",
+		0x009e => "Bose",
+		0x00e0 => "Google",
+		0x012d => "Sony",
+		0x0157 => "Anhui Huami",
+		0x0171 => "Amazon",
+		0x02e5 => "Espressif",
+		0x038f => "Xiaomi",
+		0x0499 => "Ruuvi Innovations",
+		_ => return None,
+	};
+	Some(name)
+}
+
+/// An Apple iBeacon advertisement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IBeacon {
+	pub uuid: BluetoothUuid,
+	pub major: u16,
+	pub minor: u16,
+	/// The calibrated RSSI at 1 m, in dBm.
+	pub tx_power: i8,
+}
+
+impl IBeacon {
+	/// Parses the manufacturer data of [`COMPANY_APPLE`].
+	pub fn parse(data: &[u8]) -> Option<Self> {
+		let [0x02, 0x15, rest @ ..] = data else {
+			return None;
+		};
+		let (uuid, rest) = rest.split_first_chunk::<16>()?;
+		let [major_high, major_low, minor_high, minor_low, tx_power, ..] = *rest else {
+			return None;
+		};
+		Some(Self {
+			uuid: BluetoothUuid(u128::from_be_bytes(*uuid)),
+			major: u16::from_be_bytes([major_high, major_low]),
+			minor: u16::from_be_bytes([minor_high, minor_low]),
+			tx_power: tx_power as i8,
+		})
+	}
+}
+
+/// A Google Eddystone frame.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Eddystone {
+	Uid {
+		/// The calibrated RSSI at 0 m, in dBm.
+		tx_power: i8,
+		namespace: [u8; 10],
+		instance: [u8; 6],
+	},
+	Url {
+		tx_power: i8,
+		url: String,
+	},
+	/// Telemetry of the beacon itself.
+	Tlm {
+		/// The battery voltage in mV, if measured.
+		battery_millivolts: Option<u16>,
+		/// The temperature in °C, if measured.
+		temperature: Option<f32>,
+		advertisement_count: u32,
+		/// Time since the beacon booted, in tenths of a second.
+		uptime_deciseconds: u32,
+	},
+	Eid {
+		tx_power: i8,
+		eid: [u8; 8],
+	},
+}
+
+impl Eddystone {
+	/// Parses the service data of [`EDDYSTONE_SERVICE`].
+	pub fn parse(data: &[u8]) -> Option<Self> {
+		let (&frame_type, rest) = data.split_first()?;
+		match frame_type {
+			0x00 => {
+				let (&tx_power, rest) = rest.split_first()?;
+				let (namespace, rest) = rest.split_first_chunk::<10>()?;
+				let (instance, _) = rest.split_first_chunk::<6>()?;
+				Some(Self::Uid {
+					tx_power: tx_power as i8,
+					namespace: *namespace,
+					instance: *instance,
+				})
+			}
+			0x10 => {
+				let [tx_power, scheme, encoded @ ..] = rest else {
+					return None;
+				};
+				let mut url = match scheme {
+					0x00 => "http://www.",
+					0x01 => "https://www.",
+					0x02 => "http://",
+					0x03 => "https://",
+					_ => return None,
+				}
+				.to_owned();
+				for &byte in encoded {
+					match EDDYSTONE_URL_EXPANSIONS.get(byte as usize) {
+						Some(expansion) => url.push_str(expansion),
+						None => url.push(byte as char),
+					}
+				}
+				Some(Self::Url {
+					tx_power: *tx_power as i8,
+					url,
+				})
+			}
+			0x20 => {
+				let (&0x00, rest) = rest.split_first()? else {
+					return None;
+				};
+				let (battery, rest) = rest.split_first_chunk::<2>()?;
+				let (temperature, rest) = rest.split_first_chunk::<2>()?;
+				let (advertisement_count, rest) = rest.split_first_chunk::<4>()?;
+				let (uptime, _) = rest.split_first_chunk::<4>()?;
+				let battery = u16::from_be_bytes(*battery);
+				let temperature = i16::from_be_bytes(*temperature);
+				Some(Self::Tlm {
+					battery_millivolts: (battery != 0).then_some(battery),
+					// A signed 8.8 fixed-point value, where -128 °C means unsupported.
+					temperature: (temperature != i16::MIN).then(|| f32::from(temperature) / 256.0),
+					advertisement_count: u32::from_be_bytes(*advertisement_count),
+					uptime_deciseconds: u32::from_be_bytes(*uptime),
+				})
+			}
+			0x30 => {
+				let (&tx_power, rest) = rest.split_first()?;
+				let (eid, _) = rest.split_first_chunk::<8>()?;
+				Some(Self::Eid {
+					tx_power: tx_power as i8,
+					eid: *eid,
+				})
+			}
+			_ => None,
+		}
+	}
+}
+
+const EDDYSTONE_URL_EXPANSIONS: [&str; 14] = [
+	".com/", ".org/", ".edu/", ".net/", ".info/", ".biz/", ".gov/", ".com", ".org", ".edu", ".net",
+	".info", ".biz", ".gov",
+];
+
+/// The battery status an Apple accessory such as AirPods broadcasts in its
+/// proximity pairing message. Levels are percentages in steps of 10.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AppleBatteryStatus {
+	/// The Apple model number of the accessory.
+	pub model: u16,
+	pub left: Option<u8>,
+	pub right: Option<u8>,
+	pub case: Option<u8>,
+	pub left_charging: bool,
+	pub right_charging: bool,
+	pub case_charging: bool,
+}
+
+impl AppleBatteryStatus {
+	/// Parses the manufacturer data of [`COMPANY_APPLE`].
+	pub fn parse(data: &[u8]) -> Option<Self> {
+		let [0x07, _, _, model_high, model_low, status, pods, case, ..] = *data else {
+			return None;
+		};
+		let level = |nibble: u8| (nibble <= 10).then_some(nibble * 10);
+		// The pod levels swap places depending on which pod is broadcasting.
+		let flipped = status & 0x20 == 0;
+		let (left, right) = if flipped {
+			(pods >> 4, pods & 0x0f)
+		} else {
+			(pods & 0x0f, pods >> 4)
+		};
+		let (left_charging, right_charging) = if flipped { (0x02, 0x01) } else { (0x01, 0x02) };
+		let charging = case >> 4;
+		Some(Self {
+			model: u16::from_be_bytes([model_high, model_low]),
+			left: level(left),
+			right: level(right),
+			case: level(case & 0x0f),
+			left_charging: charging & left_charging != 0,
+			right_charging: charging & right_charging != 0,
+			case_charging: charging & 0x04 != 0,
+		})
+	}
+
+	/// The lowest level among the earbuds, or the level of the case if neither
+	/// earbud reports one.
+	pub fn level(&self) -> Option<u8> {
+		match (self.left, self.right) {
+			(Some(left), Some(right)) => Some(left.min(right)),
+			(Some(level), None) | (None, Some(level)) => Some(level),
+			(None, None) => self.case,
+		}
+	}
+}
+
+/// How a device advertising Microsoft Swift Pair wants to be paired.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwiftPairScenario {
+	Le,
+	/// Pairing over BR/EDR, at the given address rather than the advertising one.
+	BrEdr {
+		address: [u8; 6],
+	},
+	/// Pairing over LE and BR/EDR with Secure Connections.
+	LeAndBrEdr,
+}
+
+/// A Microsoft Swift Pair advertisement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwiftPair {
+	pub scenario: SwiftPairScenario,
+	/// The RSSI below which the device should not be offered for pairing.
+	pub rssi: i8,
+	pub class: Option<DeviceClass>,
+	pub display_name: Option<String>,
+}
+
+impl SwiftPair {
+	/// Parses the manufacturer data of [`COMPANY_MICROSOFT`].
+	pub fn parse(data: &[u8]) -> Option<Self> {
+		let [0x03, scenario, rssi, rest @ ..] = data else {
+			return None;
+		};
+		let class =
+			|bytes: &[u8; 3]| DeviceClass(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]));
+		let (scenario, class, name) = match scenario {
+			0x00 => (SwiftPairScenario::Le, None, rest),
+			0x01 => {
+				let (address, rest) = rest.split_first_chunk::<6>()?;
+				let (device_class, rest) = rest.split_first_chunk::<3>()?;
+				let mut address = *address;
+				address.reverse();
+				(
+					SwiftPairScenario::BrEdr { address },
+					Some(class(device_class)),
+					rest,
+				)
+			}
+			0x02 => {
+				let (device_class, rest) = rest.split_first_chunk::<3>()?;
+				(
+					SwiftPairScenario::LeAndBrEdr,
+					Some(class(device_class)),
+					rest,
+				)
+			}
+			_ => return None,
+		};
+		Some(Self {
+			scenario,
+			rssi: *rssi as i8,
+			class,
+			display_name: (!name.is_empty()).then(|| String::from_utf8_lossy(name).into_owned()),
+		})
+	}
+}
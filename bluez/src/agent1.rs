@@ -7,10 +7,46 @@ use futures_channel::{mpsc, oneshot};
 use futures_util::SinkExt;
 use zbus::zvariant::OwnedObjectPath;
 
+use crate::uuid::BluetoothUuid;
+
 pub fn create() -> (Agent, mpsc::Receiver<Message>) {
 	let (message_sender, message_receiver) = futures_channel::mpsc::channel(1);
 
-	(Agent { message_sender }, message_receiver)
+	(
+		Agent {
+			message_sender,
+			service_policy: ServicePolicy::default(),
+		},
+		message_receiver,
+	)
+}
+
+/// Decides which `AuthorizeService` requests are accepted without asking.
+/// Requests that are not accepted are forwarded as [`Message::AuthorizeService`].
+#[derive(Clone, Debug)]
+pub struct ServicePolicy {
+	/// Accept every service of devices marked as trusted.
+	pub accept_trusted: bool,
+	/// Service UUIDs accepted for any device.
+	pub allowed_uuids: Vec<BluetoothUuid>,
+}
+
+impl Default for ServicePolicy {
+	fn default() -> Self {
+		Self {
+			accept_trusted: true,
+			allowed_uuids: Vec::new(),
+		}
+	}
+}
+
+impl ServicePolicy {
+	/// Compares UUIDs in their full 128-bit form, so that an allowed short UUID
+	/// matches the long form BlueZ sends.
+	fn allows_uuid(&self, uuid: &str) -> bool {
+		uuid.parse::<BluetoothUuid>()
+			.is_ok_and(|uuid| self.allowed_uuids.contains(&uuid))
+	}
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -39,6 +75,7 @@ pub enum Message {
 	AuthorizeService {
 		device: OwnedObjectPath,
 		uuid: String,
+		response: oneshot::Sender<bool>,
 	},
 	Cancel,
 	DisplayPasskey {
@@ -72,6 +109,28 @@ pub enum Message {
 
 pub struct Agent {
 	pub(self) message_sender: mpsc::Sender<Message>,
+	pub(self) service_policy: ServicePolicy,
+}
+
+impl Agent {
+	pub fn with_service_policy(mut self, service_policy: ServicePolicy) -> Self {
+		self.service_policy = service_policy;
+		self
+	}
+
+	async fn is_trusted(connection: &zbus::Connection, device: &OwnedObjectPath) -> bool {
+		let Ok(builder) = crate::device1::Device1Proxy::builder(connection).path(device) else {
+			return false;
+		};
+		match builder
+			.cache_properties(zbus::proxy::CacheProperties::No)
+			.build()
+			.await
+		{
+			Ok(device) => device.trusted().await.unwrap_or(false),
+			Err(_) => false,
+		}
+	}
 }
 
 #[zbus::interface(name = "org.bluez.Agent1")]
@@ -80,12 +139,34 @@ impl Agent {
 	/// needs to authorize a connection/service request.
 	async fn authorize_service(
 		&mut self,
+		#[zbus(connection)] connection: &zbus::Connection,
 		device: OwnedObjectPath,
 		uuid: String,
 	) -> zbus::fdo::Result<()> {
 		tracing::debug!(?device, uuid, "authorize_service");
 
-		Ok(())
+		if self.service_policy.allows_uuid(&uuid)
+			|| (self.service_policy.accept_trusted && Self::is_trusted(connection, &device).await)
+		{
+			return Ok(());
+		}
+
+		let (response, response_rx) = oneshot::channel::<bool>();
+
+		_ = self
+			.message_sender
+			.send(Message::AuthorizeService {
+				device,
+				uuid,
+				response,
+			})
+			.await;
+
+		match response_rx.await {
+			Ok(true) => Ok(()),
+			Ok(false) => Err(zbus::fdo::Error::Failed("cancelled".to_string())),
+			Err(why) => Err(zbus::fdo::Error::Failed(why.to_string())),
+		}
 	}
 
 	/// This method gets called to indicate that the agent request
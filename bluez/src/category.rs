@@ -0,0 +1,254 @@
+// Copyright 2024 System76 <info@system76.com>
+// SPDX-License-Identifier: MPL-2.0
+
+//! Decoding of the Class of Device and GAP Appearance of a device.
+
+use bitflags::bitflags;
+
+/// What kind of device a device is, as far as its class or appearance tells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DeviceCategory {
+	Computer,
+	Phone,
+	Modem,
+	NetworkAccessPoint,
+	Headset,
+	Headphones,
+	Speaker,
+	Microphone,
+	MediaPlayer,
+	VideoCamera,
+	Display,
+	Keyboard,
+	Mouse,
+	Joystick,
+	Gamepad,
+	Tablet,
+	RemoteControl,
+	Printer,
+	Scanner,
+	Camera,
+	Watch,
+	Wearable,
+	Toy,
+	Health,
+	Sensor,
+	Tag,
+	#[default]
+	Unknown,
+}
+
+impl DeviceCategory {
+	/// Decodes the category from the `Class` and `Appearance` properties,
+	/// preferring the class as BlueZ does.
+	pub fn from_class_and_appearance(class: Option<u32>, appearance: Option<u16>) -> Self {
+		class
+			.map(|class| DeviceClass(class).category())
+			.filter(|category| *category != Self::Unknown)
+			.or_else(|| appearance.map(|appearance| Appearance(appearance).category()))
+			.unwrap_or_default()
+	}
+
+	/// An icon name for the category. Categories BlueZ derives an `Icon` for
+	/// use its name, the others the nearest icon of the freedesktop icon naming
+	/// specification, which has none for wearables, toys, health devices,
+	/// sensors or tags.
+	pub fn icon_name(self) -> &'static str {
+		match self {
+			Self::Computer => "computer",
+			Self::Phone => "phone",
+			Self::Modem => "modem",
+			Self::NetworkAccessPoint => "network-wireless",
+			Self::Headset => "audio-headset",
+			Self::Headphones => "audio-headphones",
+			Self::Speaker | Self::Microphone => "audio-card",
+			Self::MediaPlayer => "multimedia-player",
+			Self::VideoCamera => "camera-video",
+			Self::Display => "video-display",
+			Self::Keyboard => "input-keyboard",
+			Self::Mouse => "input-mouse",
+			Self::Joystick | Self::Gamepad | Self::RemoteControl | Self::Toy => "input-gaming",
+			Self::Tablet => "input-tablet",
+			Self::Printer => "printer",
+			Self::Scanner => "scanner",
+			Self::Camera => "camera-photo",
+			Self::Watch
+			| Self::Wearable
+			| Self::Health
+			| Self::Sensor
+			| Self::Tag
+			| Self::Unknown => "unknown",
+		}
+	}
+}
+
+/// The major device classes of a Class of Device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MajorClass {
+	Miscellaneous,
+	Computer,
+	Phone,
+	NetworkAccessPoint,
+	AudioVideo,
+	Peripheral,
+	Imaging,
+	Wearable,
+	Toy,
+	Health,
+	Uncategorized,
+	Reserved(u8),
+}
+
+impl From<u8> for MajorClass {
+	fn from(major: u8) -> Self {
+		match major {
+			0x00 => Self::Miscellaneous,
+			0x01 => Self::Computer,
+			0x02 => Self::Phone,
+			0x03 => Self::NetworkAccessPoint,
+			0x04 => Self::AudioVideo,
+			0x05 => Self::Peripheral,
+			0x06 => Self::Imaging,
+			0x07 => Self::Wearable,
+			0x08 => Self::Toy,
+			0x09 => Self::Health,
+			0x1f => Self::Uncategorized,
+			major => Self::Reserved(major),
+		}
+	}
+}
+
+bitflags! {
+	/// The major service classes of a Class of Device.
+	#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+	pub struct MajorServiceClasses: u16 {
+		const LIMITED_DISCOVERABLE = 0x0001;
+		const LE_AUDIO = 0x0002;
+		const POSITIONING = 0x0008;
+		const NETWORKING = 0x0010;
+		const RENDERING = 0x0020;
+		const CAPTURING = 0x0040;
+		const OBJECT_TRANSFER = 0x0080;
+		const AUDIO = 0x0100;
+		const TELEPHONY = 0x0200;
+		const INFORMATION = 0x0400;
+	}
+}
+
+/// A Class of Device, as found in the `Class` property of `org.bluez.Device1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DeviceClass(pub u32);
+
+impl DeviceClass {
+	pub fn major(self) -> MajorClass {
+		MajorClass::from(((self.0 >> 8) & 0x1f) as u8)
+	}
+
+	/// The minor device class, whose meaning depends on the major class.
+	pub fn minor(self) -> u8 {
+		((self.0 >> 2) & 0x3f) as u8
+	}
+
+	pub fn services(self) -> MajorServiceClasses {
+		MajorServiceClasses::from_bits_truncate((self.0 >> 13) as u16)
+	}
+
+	pub fn category(self) -> DeviceCategory {
+		let minor = self.minor();
+		match self.major() {
+			MajorClass::Computer => DeviceCategory::Computer,
+			MajorClass::Phone => match minor {
+				0x04 => DeviceCategory::Modem,
+				_ => DeviceCategory::Phone,
+			},
+			MajorClass::NetworkAccessPoint => DeviceCategory::NetworkAccessPoint,
+			MajorClass::AudioVideo => match minor {
+				0x01 | 0x02 => DeviceCategory::Headset,
+				0x04 => DeviceCategory::Microphone,
+				0x05 | 0x08 | 0x0a => DeviceCategory::Speaker,
+				0x06 => DeviceCategory::Headphones,
+				0x07 | 0x0b => DeviceCategory::MediaPlayer,
+				0x0c | 0x0d => DeviceCategory::VideoCamera,
+				0x09 | 0x0e | 0x0f | 0x10 => DeviceCategory::Display,
+				0x12 => DeviceCategory::Toy,
+				_ => DeviceCategory::Unknown,
+			},
+			// The upper two bits tell keyboards and pointing devices apart, the
+			// lower four bits the kind of other input devices.
+			MajorClass::Peripheral => match (minor >> 4, minor & 0x0f) {
+				(_, 0x01) => DeviceCategory::Joystick,
+				(_, 0x02) => DeviceCategory::Gamepad,
+				(_, 0x03) => DeviceCategory::RemoteControl,
+				(_, 0x05) => DeviceCategory::Tablet,
+				(0x01, _) => DeviceCategory::Keyboard,
+				(0x02, _) => DeviceCategory::Mouse,
+				(0x03, _) => DeviceCategory::Keyboard,
+				_ => DeviceCategory::Unknown,
+			},
+			// The imaging minor class is a bit field, of which the first set
+			// bit in this order wins.
+			MajorClass::Imaging => {
+				if minor & 0x20 != 0 {
+					DeviceCategory::Printer
+				} else if minor & 0x08 != 0 {
+					DeviceCategory::Camera
+				} else if minor & 0x10 != 0 {
+					DeviceCategory::Scanner
+				} else if minor & 0x04 != 0 {
+					DeviceCategory::Display
+				} else {
+					DeviceCategory::Unknown
+				}
+			}
+			MajorClass::Wearable => match minor {
+				0x01 => DeviceCategory::Watch,
+				_ => DeviceCategory::Wearable,
+			},
+			MajorClass::Toy => DeviceCategory::Toy,
+			MajorClass::Health => DeviceCategory::Health,
+			_ => DeviceCategory::Unknown,
+		}
+	}
+}
+
+/// A GAP Appearance, as found in the `Appearance` property of `org.bluez.Device1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Appearance(pub u16);
+
+impl Appearance {
+	/// The category, the upper ten bits of the appearance.
+	pub fn category_value(self) -> u16 {
+		self.0 >> 6
+	}
+
+	/// The subcategory, the lower six bits of the appearance.
+	pub fn subcategory(self) -> u8 {
+		(self.0 & 0x3f) as u8
+	}
+
+	pub fn category(self) -> DeviceCategory {
+		match (self.category_value(), self.subcategory()) {
+			(0x001, _) => DeviceCategory::Phone,
+			(0x002, _) => DeviceCategory::Computer,
+			(0x003, _) => DeviceCategory::Watch,
+			(0x005, _) => DeviceCategory::Display,
+			(0x006, _) => DeviceCategory::RemoteControl,
+			(0x007, _) => DeviceCategory::Wearable,
+			(0x008, _) | (0x009, _) => DeviceCategory::Tag,
+			(0x00a, _) => DeviceCategory::MediaPlayer,
+			(0x00b, _) => DeviceCategory::Scanner,
+			(0x00c..=0x00e, _) | (0x010, _) | (0x031, _) => DeviceCategory::Health,
+			(0x00f, 0x01) => DeviceCategory::Keyboard,
+			(0x00f, 0x02) => DeviceCategory::Mouse,
+			(0x00f, 0x03) => DeviceCategory::Joystick,
+			(0x00f, 0x04) => DeviceCategory::Gamepad,
+			(0x00f, 0x05) => DeviceCategory::Tablet,
+			(0x00f, 0x08) => DeviceCategory::Scanner,
+			(0x011, _) | (0x012, _) => DeviceCategory::Sensor,
+			(0x021, _) => DeviceCategory::Speaker,
+			(0x025, 0x01) | (0x025, 0x03) => DeviceCategory::Headphones,
+			(0x025, _) => DeviceCategory::Headset,
+			_ => DeviceCategory::Unknown,
+		}
+	}
+}
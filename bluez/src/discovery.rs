@@ -0,0 +1,208 @@
+// Copyright 2024 System76 <info@system76.com>
+// SPDX-License-Identifier: MPL-2.0
+
+//! Scoped device discovery on an adapter.
+
+use std::collections::HashMap;
+
+use futures_util::{Stream, StreamExt};
+use zbus::zvariant::{OwnedObjectPath, Value};
+
+use crate::{
+	DeviceSnapshot,
+	adapter1::Adapter1Proxy,
+	manager::{Signal, signals},
+	uuid::BluetoothUuid,
+};
+
+const DEVICE_INTERFACE: &str = "org.bluez.Device1";
+
+/// The transport to discover devices on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Transport {
+	/// Interleaved scanning of both transports, where supported by the adapter.
+	#[default]
+	Auto,
+	/// BR/EDR inquiry only.
+	BrEdr,
+	/// LE scanning only.
+	Le,
+}
+
+impl From<Transport> for &'static str {
+	fn from(transport: Transport) -> &'static str {
+		match transport {
+			Transport::Auto => "auto",
+			Transport::BrEdr => "bredr",
+			Transport::Le => "le",
+		}
+	}
+}
+
+/// Filters applied to discovery with `SetDiscoveryFilter`. Unset fields keep
+/// BlueZ's defaults.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiscoveryFilter {
+	/// Only report devices advertising at least one of these service UUIDs.
+	pub uuids: Vec<BluetoothUuid>,
+	/// Only report devices with an RSSI above this threshold, in dBm.
+	pub rssi: Option<i16>,
+	/// Only report devices with a pathloss below this threshold, in dB. Cannot
+	/// be combined with `rssi`.
+	pub pathloss: Option<u16>,
+	pub transport: Option<Transport>,
+	/// Report every advertisement, rather than only those whose data changed.
+	pub duplicate_data: Option<bool>,
+	/// Only report devices in discoverable mode.
+	pub discoverable: Option<bool>,
+	/// Only report devices whose address or name starts with this prefix.
+	pub pattern: Option<String>,
+}
+
+impl DiscoveryFilter {
+	fn to_properties(&self) -> HashMap<&'static str, Value<'_>> {
+		let mut properties = HashMap::new();
+		if !self.uuids.is_empty() {
+			let uuids: Vec<String> = self.uuids.iter().map(ToString::to_string).collect();
+			properties.insert("UUIDs", Value::from(uuids));
+		}
+		if let Some(rssi) = self.rssi {
+			properties.insert("RSSI", Value::from(rssi));
+		}
+		if let Some(pathloss) = self.pathloss {
+			properties.insert("Pathloss", Value::from(pathloss));
+		}
+		if let Some(transport) = self.transport {
+			properties.insert("Transport", Value::from(<&str>::from(transport)));
+		}
+		if let Some(duplicate_data) = self.duplicate_data {
+			properties.insert("DuplicateData", Value::from(duplicate_data));
+		}
+		if let Some(discoverable) = self.discoverable {
+			properties.insert("Discoverable", Value::from(discoverable));
+		}
+		if let Some(pattern) = &self.pattern {
+			properties.insert("Pattern", Value::from(pattern.as_str()));
+		}
+		properties
+	}
+}
+
+/// Discovery running on an adapter for as long as the session is alive.
+///
+/// Discovery is stopped by [`Self::stop`], or in the background when the
+/// session is dropped.
+#[derive(Debug)]
+pub struct DiscoverySession<'a> {
+	adapter: Adapter1Proxy<'a>,
+	active: bool,
+}
+
+impl<'a> DiscoverySession<'a> {
+	/// Apply `filter` and start discovery on the adapter.
+	pub async fn start(adapter: Adapter1Proxy<'a>, filter: &DiscoveryFilter) -> zbus::Result<Self> {
+		let properties = filter.to_properties();
+		adapter
+			.set_discovery_filter(
+				properties
+					.iter()
+					.map(|(key, value)| (*key, value))
+					.collect(),
+			)
+			.await?;
+		adapter.start_discovery().await?;
+		Ok(Self {
+			adapter,
+			active: true,
+		})
+	}
+
+	pub fn adapter(&self) -> &Adapter1Proxy<'a> {
+		&self.adapter
+	}
+
+	/// Devices seen on the adapter while the session runs: devices appearing
+	/// for the first time, and devices BlueZ already knew about whose `RSSI` or
+	/// `ManufacturerData` changes because they were found again.
+	pub async fn devices(&self) -> zbus::Result<impl Stream<Item = DeviceSnapshot> + use<>> {
+		let connection = self.adapter.inner().connection();
+		let prefix = format!("{}/", self.adapter.inner().path());
+		let signals = signals(connection).await?;
+		let object_manager =
+			zbus::fdo::ObjectManagerProxy::new(connection, "org.bluez", "/").await?;
+		let mut known: HashMap<OwnedObjectPath, DeviceSnapshot> = object_manager
+			.get_managed_objects()
+			.await?
+			.into_iter()
+			.filter(|(path, _)| path.as_str().starts_with(&prefix))
+			.filter_map(|(path, interfaces)| {
+				DeviceSnapshot::new(path.clone(), interfaces).map(|device| (path, device))
+			})
+			.collect();
+		Ok(signals.filter_map(move |signal| {
+			let seen = match signal {
+				Signal::InterfacesAdded(path, interfaces) if path.as_str().starts_with(&prefix) => {
+					DeviceSnapshot::new(path.clone(), interfaces).inspect(|device| {
+						known.insert(path, device.clone());
+					})
+				}
+				Signal::InterfacesRemoved(path, interfaces)
+					if interfaces.iter().any(|name| name == DEVICE_INTERFACE) =>
+				{
+					known.remove(&path);
+					None
+				}
+				Signal::PropertiesChanged {
+					path,
+					interface,
+					changed,
+					invalidated,
+				} if interface == DEVICE_INTERFACE => known.get_mut(&path).and_then(|device| {
+					let found_again =
+						changed.contains_key("RSSI") || changed.contains_key("ManufacturerData");
+					device.update_device(changed, &invalidated);
+					found_again.then(|| device.clone())
+				}),
+				_ => None,
+			};
+			futures_util::future::ready(seen)
+		}))
+	}
+
+	/// Stop discovery and clear the discovery filter.
+	pub async fn stop(mut self) -> zbus::Result<()> {
+		self.active = false;
+		self.adapter.stop_discovery().await
+	}
+}
+
+impl Drop for DiscoverySession<'_> {
+	fn drop(&mut self) {
+		if !self.active {
+			return;
+		}
+		let connection = self.adapter.inner().connection().clone();
+		let path = self.adapter.inner().path().to_owned();
+		let stop = {
+			let connection = connection.clone();
+			async move {
+				let result = connection
+					.call_method(
+						Some("org.bluez"),
+						path,
+						Some("org.bluez.Adapter1"),
+						"StopDiscovery",
+						&(),
+					)
+					.await;
+				if let Err(why) = result {
+					tracing::warn!("failed to stop discovery: {why}");
+				}
+			}
+		};
+		connection
+			.executor()
+			.spawn(stop, "bluez-stop-discovery")
+			.detach();
+	}
+}
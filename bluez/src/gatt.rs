@@ -0,0 +1,580 @@
+// Copyright 2024 System76 <info@system76.com>
+// SPDX-License-Identifier: MPL-2.0
+
+//! A GATT client for the services a device exposes once connected.
+
+use std::{
+	collections::HashMap,
+	os::unix::net::UnixDatagram,
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+use futures_util::{Stream, StreamExt};
+use zbus::{
+	proxy::CacheProperties,
+	zvariant::{OwnedObjectPath, OwnedValue, Value},
+};
+
+use crate::{
+	BluetoothDevice, category::Appearance, gatt_characteristic1::GattCharacteristic1Proxy,
+	gatt_descriptor1::GattDescriptor1Proxy, gatt_service1::GattService1Proxy, take_property,
+	uuid::BluetoothUuid,
+};
+
+pub const DEVICE_NAME: BluetoothUuid = BluetoothUuid::from_u16(0x2a00);
+pub const APPEARANCE: BluetoothUuid = BluetoothUuid::from_u16(0x2a01);
+pub const BATTERY_LEVEL: BluetoothUuid = BluetoothUuid::from_u16(0x2a19);
+pub const SYSTEM_ID: BluetoothUuid = BluetoothUuid::from_u16(0x2a23);
+pub const MODEL_NUMBER: BluetoothUuid = BluetoothUuid::from_u16(0x2a24);
+pub const SERIAL_NUMBER: BluetoothUuid = BluetoothUuid::from_u16(0x2a25);
+pub const FIRMWARE_REVISION: BluetoothUuid = BluetoothUuid::from_u16(0x2a26);
+pub const HARDWARE_REVISION: BluetoothUuid = BluetoothUuid::from_u16(0x2a27);
+pub const SOFTWARE_REVISION: BluetoothUuid = BluetoothUuid::from_u16(0x2a28);
+pub const MANUFACTURER_NAME: BluetoothUuid = BluetoothUuid::from_u16(0x2a29);
+pub const HEART_RATE_MEASUREMENT: BluetoothUuid = BluetoothUuid::from_u16(0x2a37);
+pub const PNP_ID: BluetoothUuid = BluetoothUuid::from_u16(0x2a50);
+
+const SERVICE_INTERFACE: &str = "org.bluez.GattService1";
+const CHARACTERISTIC_INTERFACE: &str = "org.bluez.GattCharacteristic1";
+const DESCRIPTOR_INTERFACE: &str = "org.bluez.GattDescriptor1";
+
+/// How a characteristic value is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteType {
+	/// Write without response.
+	Command,
+	/// Write with response.
+	Request,
+	/// Reliable write, verified by the remote device before it is executed.
+	Reliable,
+}
+
+impl From<WriteType> for &'static str {
+	fn from(write_type: WriteType) -> &'static str {
+		match write_type {
+			WriteType::Command => "command",
+			WriteType::Request => "request",
+			WriteType::Reliable => "reliable",
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReadOptions {
+	/// The offset into the value to read from.
+	pub offset: u16,
+}
+
+impl ReadOptions {
+	fn to_options(self) -> HashMap<&'static str, Value<'static>> {
+		let mut options = HashMap::new();
+		if self.offset != 0 {
+			options.insert("offset", Value::from(self.offset));
+		}
+		options
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+	/// The offset into the value to write at.
+	pub offset: u16,
+	/// The write procedure, picked by BlueZ from the characteristic flags if unset.
+	pub write_type: Option<WriteType>,
+}
+
+impl WriteOptions {
+	fn to_options(self) -> HashMap<&'static str, Value<'static>> {
+		let mut options = HashMap::new();
+		if self.offset != 0 {
+			options.insert("offset", Value::from(self.offset));
+		}
+		if let Some(write_type) = self.write_type {
+			options.insert("type", Value::from(<&str>::from(write_type)));
+		}
+		options
+	}
+}
+
+fn borrow_options<'a>(
+	options: &'a HashMap<&'static str, Value<'static>>,
+) -> HashMap<&'a str, &'a Value<'a>> {
+	options.iter().map(|(key, value)| (*key, value)).collect()
+}
+
+/// A GATT service of a device, with its characteristics.
+#[derive(Debug, Clone)]
+pub struct GattService<'a> {
+	pub proxy: GattService1Proxy<'a>,
+	pub uuid: BluetoothUuid,
+	pub primary: bool,
+	pub characteristics: Vec<GattCharacteristic<'a>>,
+}
+
+impl<'a> GattService<'a> {
+	pub fn characteristic(&self, uuid: BluetoothUuid) -> Option<&GattCharacteristic<'a>> {
+		self.characteristics
+			.iter()
+			.find(|characteristic| characteristic.uuid == uuid)
+	}
+}
+
+/// A characteristic of a GATT service, with its descriptors.
+#[derive(Debug, Clone)]
+pub struct GattCharacteristic<'a> {
+	pub proxy: GattCharacteristic1Proxy<'a>,
+	pub uuid: BluetoothUuid,
+	/// The supported operations, such as `read`, `write` and `notify`.
+	pub flags: Vec<String>,
+	pub descriptors: Vec<GattDescriptor<'a>>,
+}
+
+impl<'a> GattCharacteristic<'a> {
+	pub fn has_flag(&self, flag: &str) -> bool {
+		self.flags.iter().any(|supported| supported == flag)
+	}
+
+	pub async fn read(&self, options: ReadOptions) -> zbus::Result<Vec<u8>> {
+		let options = options.to_options();
+		self.proxy.read_value(borrow_options(&options)).await
+	}
+
+	/// Read the value and decode it, if it is of a known characteristic.
+	pub async fn read_decoded(&self) -> zbus::Result<Option<CharacteristicValue>> {
+		let value = self.read(ReadOptions::default()).await?;
+		Ok(CharacteristicValue::decode(self.uuid, &value))
+	}
+
+	pub async fn write(&self, value: &[u8], options: WriteOptions) -> zbus::Result<()> {
+		let options = options.to_options();
+		self.proxy
+			.write_value(value, borrow_options(&options))
+			.await
+	}
+
+	/// Subscribe to notifications or indications with `StartNotify`, receiving
+	/// each new value. Notifications continue until the stream is dropped or
+	/// the device disconnects.
+	pub async fn notify(&self) -> zbus::Result<Notifications> {
+		let connection = self.proxy.inner().connection();
+		let path = self.proxy.inner().path().to_owned();
+		let rule = zbus::MatchRule::builder()
+			.msg_type(zbus::message::Type::Signal)
+			.sender("org.bluez")?
+			.interface("org.freedesktop.DBus.Properties")?
+			.member("PropertiesChanged")?
+			.path(path.clone())?
+			.arg(0, CHARACTERISTIC_INTERFACE)?
+			.build();
+		let messages = zbus::MessageStream::for_match_rule(rule, connection, None).await?;
+		self.proxy.start_notify().await?;
+		Ok(Notifications {
+			messages,
+			connection: connection.clone(),
+			path: path.into(),
+		})
+	}
+
+	pub async fn stop_notify(&self) -> zbus::Result<()> {
+		self.proxy.stop_notify().await
+	}
+
+	/// Subscribe to notifications with `AcquireNotify`, receiving values over a
+	/// dedicated socket rather than the bus. The stream ends once the socket is
+	/// closed, which happens when the device disconnects. Notifications stop
+	/// when the stream is dropped.
+	pub async fn acquire_notify(&self) -> zbus::Result<impl Stream<Item = Vec<u8>> + use<>> {
+		let (fd, mtu) = self.proxy.acquire_notify(HashMap::new()).await?;
+		let socket = async_io::Async::new(UnixDatagram::from(std::os::fd::OwnedFd::from(fd)))
+			.map_err(zbus::Error::from)?;
+		Ok(futures_util::stream::unfold(
+			socket,
+			move |socket| async move {
+				let mut buffer = vec![0; usize::from(mtu)];
+				match socket.recv(&mut buffer).await {
+					Ok(0) | Err(_) => None,
+					Ok(length) => {
+						buffer.truncate(length);
+						Some((buffer, socket))
+					}
+				}
+			},
+		))
+	}
+}
+
+/// The values of a characteristic as they are notified, from
+/// [`GattCharacteristic::notify`]. Each notification is read from its own
+/// `PropertiesChanged` signal, so none are merged or replaced by a cached value.
+/// Notifications stop when the stream is dropped.
+#[derive(Debug)]
+pub struct Notifications {
+	messages: zbus::MessageStream,
+	connection: zbus::Connection,
+	path: OwnedObjectPath,
+}
+
+impl Stream for Notifications {
+	type Item = Vec<u8>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		loop {
+			let Some(message) = std::task::ready!(self.messages.poll_next_unpin(cx)) else {
+				return Poll::Ready(None);
+			};
+			let Some(signal) = message
+				.ok()
+				.and_then(zbus::fdo::PropertiesChanged::from_message)
+			else {
+				continue;
+			};
+			let value = signal.args().ok().and_then(|args| {
+				let value = args
+					.changed_properties()
+					.get("Value")?
+					.try_to_owned()
+					.ok()?;
+				Vec::<u8>::try_from(value).ok()
+			});
+			if let Some(value) = value {
+				return Poll::Ready(Some(value));
+			}
+		}
+	}
+}
+
+impl Drop for Notifications {
+	fn drop(&mut self) {
+		let path = self.path.clone();
+		let stop = {
+			let connection = self.connection.clone();
+			async move {
+				let result = connection
+					.call_method(
+						Some("org.bluez"),
+						path,
+						Some(CHARACTERISTIC_INTERFACE),
+						"StopNotify",
+						&(),
+					)
+					.await;
+				if let Err(why) = result {
+					tracing::warn!("failed to stop notifications: {why}");
+				}
+			}
+		};
+		self.connection
+			.executor()
+			.spawn(stop, "bluez-stop-notify")
+			.detach();
+	}
+}
+
+/// A descriptor of a GATT characteristic.
+#[derive(Debug, Clone)]
+pub struct GattDescriptor<'a> {
+	pub proxy: GattDescriptor1Proxy<'a>,
+	pub uuid: BluetoothUuid,
+}
+
+impl GattDescriptor<'_> {
+	pub async fn read(&self, options: ReadOptions) -> zbus::Result<Vec<u8>> {
+		let options = options.to_options();
+		self.proxy.read_value(borrow_options(&options)).await
+	}
+
+	pub async fn write(&self, value: &[u8], options: WriteOptions) -> zbus::Result<()> {
+		let options = options.to_options();
+		self.proxy
+			.write_value(value, borrow_options(&options))
+			.await
+	}
+}
+
+/// The vendor and product of a device, from the PnP ID characteristic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PnpId {
+	/// 1 if the vendor is a Bluetooth company identifier, 2 if a USB vendor ID.
+	pub vendor_id_source: u8,
+	pub vendor_id: u16,
+	pub product_id: u16,
+	pub product_version: u16,
+}
+
+/// A decoded value of a standard characteristic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CharacteristicValue {
+	DeviceName(String),
+	Appearance(Appearance),
+	/// The battery level, in percent.
+	BatteryLevel(u8),
+	SystemId(u64),
+	ModelNumber(String),
+	SerialNumber(String),
+	FirmwareRevision(String),
+	HardwareRevision(String),
+	SoftwareRevision(String),
+	ManufacturerName(String),
+	/// The heart rate, in beats per minute.
+	HeartRate(u16),
+	PnpId(PnpId),
+}
+
+impl CharacteristicValue {
+	/// Decodes the value of the characteristic `uuid`, returning `None` for
+	/// unknown characteristics and malformed values.
+	pub fn decode(uuid: BluetoothUuid, value: &[u8]) -> Option<Self> {
+		let string = || {
+			String::from_utf8_lossy(value)
+				.trim_end_matches('\0')
+				.to_owned()
+		};
+		let decoded = match uuid {
+			DEVICE_NAME => Self::DeviceName(string()),
+			APPEARANCE => {
+				Self::Appearance(Appearance(u16::from_le_bytes(*value.first_chunk::<2>()?)))
+			}
+			BATTERY_LEVEL => Self::BatteryLevel(*value.first()?),
+			SYSTEM_ID => Self::SystemId(u64::from_le_bytes(*value.first_chunk::<8>()?)),
+			MODEL_NUMBER => Self::ModelNumber(string()),
+			SERIAL_NUMBER => Self::SerialNumber(string()),
+			FIRMWARE_REVISION => Self::FirmwareRevision(string()),
+			HARDWARE_REVISION => Self::HardwareRevision(string()),
+			SOFTWARE_REVISION => Self::SoftwareRevision(string()),
+			MANUFACTURER_NAME => Self::ManufacturerName(string()),
+			HEART_RATE_MEASUREMENT => {
+				// The first bit of the flags tells whether the rate is 8 or 16 bits.
+				let (&flags, rest) = value.split_first()?;
+				let rate = if flags & 0x01 == 0 {
+					u16::from(*rest.first()?)
+				} else {
+					u16::from_le_bytes(*rest.first_chunk::<2>()?)
+				};
+				Self::HeartRate(rate)
+			}
+			PNP_ID => {
+				let [
+					source,
+					vendor_low,
+					vendor_high,
+					product_low,
+					product_high,
+					version_low,
+					version_high,
+					..,
+				] = *value
+				else {
+					return None;
+				};
+				Self::PnpId(PnpId {
+					vendor_id_source: source,
+					vendor_id: u16::from_le_bytes([vendor_low, vendor_high]),
+					product_id: u16::from_le_bytes([product_low, product_high]),
+					product_version: u16::from_le_bytes([version_low, version_high]),
+				})
+			}
+			_ => return None,
+		};
+		Some(decoded)
+	}
+}
+
+/// The contents of the Device Information service.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeviceInformation {
+	pub manufacturer_name: Option<String>,
+	pub model_number: Option<String>,
+	pub serial_number: Option<String>,
+	pub hardware_revision: Option<String>,
+	pub firmware_revision: Option<String>,
+	pub software_revision: Option<String>,
+	pub pnp_id: Option<PnpId>,
+	/// The readable characteristics that failed to be read, such as those the
+	/// device protects with `NotPermitted` or `NotAuthorized`.
+	pub unreadable: Vec<BluetoothUuid>,
+}
+
+impl DeviceInformation {
+	/// Reads every readable characteristic of the Device Information service
+	/// among `services`. Characteristics that fail to be read are recorded in
+	/// [`Self::unreadable`] and the others are still read.
+	pub async fn read(services: &[GattService<'_>]) -> Self {
+		let mut information = Self::default();
+		let Some(service) = services
+			.iter()
+			.find(|service| service.uuid == crate::uuid::ServiceClass::DeviceInformation.uuid())
+		else {
+			return information;
+		};
+		for characteristic in &service.characteristics {
+			if !characteristic.has_flag("read") {
+				continue;
+			}
+			let value = match characteristic.read_decoded().await {
+				Ok(value) => value,
+				Err(why) => {
+					tracing::debug!(uuid = %characteristic.uuid, "failed to read characteristic: {why}");
+					information.unreadable.push(characteristic.uuid);
+					continue;
+				}
+			};
+			match value {
+				Some(CharacteristicValue::ManufacturerName(name)) => {
+					information.manufacturer_name = Some(name)
+				}
+				Some(CharacteristicValue::ModelNumber(model)) => {
+					information.model_number = Some(model)
+				}
+				Some(CharacteristicValue::SerialNumber(serial)) => {
+					information.serial_number = Some(serial)
+				}
+				Some(CharacteristicValue::HardwareRevision(revision)) => {
+					information.hardware_revision = Some(revision)
+				}
+				Some(CharacteristicValue::FirmwareRevision(revision)) => {
+					information.firmware_revision = Some(revision)
+				}
+				Some(CharacteristicValue::SoftwareRevision(revision)) => {
+					information.software_revision = Some(revision)
+				}
+				Some(CharacteristicValue::PnpId(pnp_id)) => information.pnp_id = Some(pnp_id),
+				_ => (),
+			}
+		}
+		information
+	}
+}
+
+impl<'a> BluetoothDevice<'a> {
+	/// Wait until BlueZ has discovered the GATT services of the connected device.
+	pub async fn wait_services_resolved(&self) -> zbus::Result<()> {
+		let mut changes = self.device.receive_services_resolved_changed().await;
+		if self.device.services_resolved().await? {
+			return Ok(());
+		}
+		while let Some(change) = changes.next().await {
+			if change.get().await? {
+				return Ok(());
+			}
+		}
+		Err(zbus::Error::Failure(
+			"device property stream ended before services were resolved".to_owned(),
+		))
+	}
+
+	/// The GATT services of the device, with their characteristics and
+	/// descriptors, once BlueZ has resolved them.
+	pub async fn gatt_services(&self) -> zbus::Result<Vec<GattService<'a>>> {
+		self.wait_services_resolved().await?;
+		let connection = self.device.inner().connection();
+		let prefix = format!("{}/", self.device.inner().path());
+		let object_manager =
+			zbus::fdo::ObjectManagerProxy::new(connection, "org.bluez", "/").await?;
+
+		let mut services = Vec::new();
+		let mut characteristics = Vec::new();
+		let mut descriptors = Vec::new();
+		for (path, mut interfaces) in object_manager.get_managed_objects().await? {
+			if !path.as_str().starts_with(&prefix) {
+				continue;
+			}
+			if let Some(properties) = interfaces.remove(SERVICE_INTERFACE) {
+				services.push((path, properties));
+			} else if let Some(properties) = interfaces.remove(CHARACTERISTIC_INTERFACE) {
+				characteristics.push((path, properties));
+			} else if let Some(properties) = interfaces.remove(DESCRIPTOR_INTERFACE) {
+				descriptors.push((path, properties));
+			}
+		}
+
+		// The properties are all known from the managed objects, so the proxies
+		// are built without fetching them again.
+		let descriptors = futures_util::future::try_join_all(descriptors.into_iter().filter_map(
+			|(path, mut properties)| {
+				let characteristic =
+					take_property::<OwnedObjectPath>(&mut properties, "Characteristic")?;
+				let uuid = uuid_of(&mut properties)?;
+				Some(async move {
+					let proxy = GattDescriptor1Proxy::builder(connection)
+						.path(path)?
+						.cache_properties(CacheProperties::No)
+						.build()
+						.await?;
+					zbus::Result::Ok((characteristic, GattDescriptor { proxy, uuid }))
+				})
+			},
+		))
+		.await?;
+		let mut descriptors_of: HashMap<OwnedObjectPath, Vec<GattDescriptor<'a>>> = HashMap::new();
+		for (characteristic, descriptor) in descriptors {
+			descriptors_of
+				.entry(characteristic)
+				.or_default()
+				.push(descriptor);
+		}
+
+		let characteristics =
+			futures_util::future::try_join_all(characteristics.into_iter().filter_map(
+				|(path, mut properties)| {
+					let service = take_property::<OwnedObjectPath>(&mut properties, "Service")?;
+					let uuid = uuid_of(&mut properties)?;
+					let flags = take_property(&mut properties, "Flags").unwrap_or_default();
+					let descriptors = descriptors_of.remove(&path).unwrap_or_default();
+					Some(async move {
+						let proxy = GattCharacteristic1Proxy::builder(connection)
+							.path(path)?
+							.cache_properties(CacheProperties::No)
+							.build()
+							.await?;
+						zbus::Result::Ok((
+							service,
+							GattCharacteristic {
+								proxy,
+								uuid,
+								flags,
+								descriptors,
+							},
+						))
+					})
+				},
+			))
+			.await?;
+		let mut characteristics_of: HashMap<OwnedObjectPath, Vec<GattCharacteristic<'a>>> =
+			HashMap::new();
+		for (service, characteristic) in characteristics {
+			characteristics_of
+				.entry(service)
+				.or_default()
+				.push(characteristic);
+		}
+
+		futures_util::future::try_join_all(services.into_iter().filter_map(
+			|(path, mut properties)| {
+				let uuid = uuid_of(&mut properties)?;
+				let primary = take_property(&mut properties, "Primary").unwrap_or(false);
+				let characteristics = characteristics_of.remove(&path).unwrap_or_default();
+				Some(async move {
+					let proxy = GattService1Proxy::builder(connection)
+						.path(path)?
+						.cache_properties(CacheProperties::No)
+						.build()
+						.await?;
+					Ok(GattService {
+						proxy,
+						uuid,
+						primary,
+						characteristics,
+					})
+				})
+			},
+		))
+		.await
+	}
+}
+
+/// The `UUID` of a GATT object, or `None` if it is missing or malformed, in
+/// which case the object is skipped.
+fn uuid_of(properties: &mut HashMap<String, OwnedValue>) -> Option<BluetoothUuid> {
+	take_property::<String>(properties, "UUID")?.parse().ok()
+}
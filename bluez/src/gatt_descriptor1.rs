@@ -0,0 +1,56 @@
+//! # D-Bus interface proxy for: `org.bluez.GattDescriptor1`
+//!
+//! This code was generated by `zbus-xmlgen` `4.1.0` from D-Bus introspection data.
+//! Source: `Interface '/org/bluez/hci0/dev_14_3F_A6_A8_16_68/service0010/char0011/desc0013' from service 'org.bluez' on system bus`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the [Writing a client proxy] section of the zbus
+//! documentation.
+//!
+//! This type implements the [D-Bus standard interfaces], (`org.freedesktop.DBus.*`) for which the
+//! following zbus API can be used:
+//!
+//! * [`zbus::fdo::IntrospectableProxy`]
+//! * [`zbus::fdo::PropertiesProxy`]
+//!
+//! Consequently `zbus-xmlgen` did not generate code for the above interfaces.
+//!
+//! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+//! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+use zbus::proxy;
+#[proxy(interface = "org.bluez.GattDescriptor1", default_service = "org.bluez")]
+pub trait GattDescriptor1 {
+	/// ReadValue method
+	fn read_value(
+		&self,
+		options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+	) -> zbus::Result<Vec<u8>>;
+
+	/// WriteValue method
+	fn write_value(
+		&self,
+		value: &[u8],
+		options: std::collections::HashMap<&str, &zbus::zvariant::Value<'_>>,
+	) -> zbus::Result<()>;
+
+	/// Characteristic property
+	#[zbus(property)]
+	fn characteristic(&self) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+
+	/// Flags property
+	#[zbus(property)]
+	fn flags(&self) -> zbus::Result<Vec<String>>;
+
+	/// Handle property
+	#[zbus(property)]
+	fn handle(&self) -> zbus::Result<u16>;
+
+	/// UUID property
+	#[zbus(property, name = "UUID")]
+	fn uuid(&self) -> zbus::Result<String>;
+
+	/// Value property
+	#[zbus(property)]
+	fn value(&self) -> zbus::Result<Vec<u8>>;
+}
@@ -6,12 +6,25 @@ use std::collections::HashMap;
 use futures_util::join;
 
 pub mod adapter1;
+pub mod advertising;
 pub mod agent1;
 pub mod agent_manager1;
 pub mod battery1;
+pub mod category;
 pub mod device1;
+pub mod discovery;
+pub mod gatt;
+pub mod gatt_characteristic1;
+pub mod gatt_descriptor1;
+pub mod gatt_service1;
 pub mod health_manager1;
+pub mod manager;
+pub mod pairing;
 pub mod profile_manager1;
+pub mod uuid;
+
+use category::DeviceCategory;
+use uuid::{BluetoothUuid, ServiceClass};
 
 pub async fn get_adapters<'a>(
 	connection: &zbus::Connection,
@@ -95,17 +108,85 @@ impl<'a> BluetoothDevice<'a> {
 		}
 	}
 
+	/// Builds the proxies for a device whose interfaces are already known from
+	/// `ObjectManager` data, so no round-trip is spent probing for a battery.
+	async fn with_battery<'b: 'a>(
+		connection: &zbus::Connection,
+		path: zbus::zvariant::ObjectPath<'b>,
+		has_battery: bool,
+	) -> zbus::Result<Self> {
+		let device = device1::Device1Proxy::builder(connection)
+			.path(&path)?
+			.build();
+		if !has_battery {
+			return Ok(Self {
+				device: device.await?,
+				battery: None,
+			});
+		}
+		let battery = battery1::Battery1Proxy::builder(connection)
+			.path(path)?
+			.build();
+		let (device, battery) = join!(device, battery);
+		Ok(Self {
+			device: device?,
+			battery: Some(battery?),
+		})
+	}
+
+	/// The icon name BlueZ reports, or one derived from the class or appearance
+	/// of the device if BlueZ omits it.
 	pub async fn icon(&self) -> String {
-		self.device
-			.inner()
-			.get_property::<String>("Icon")
-			.await
-			.unwrap_or("unknown".to_owned())
+		match self.device.inner().get_property::<String>("Icon").await {
+			Ok(icon) => icon,
+			Err(_) => self.category().await.icon_name().to_owned(),
+		}
+	}
+
+	/// The kind of device, decoded from its class or appearance.
+	pub async fn category(&self) -> DeviceCategory {
+		let (class, appearance) = join!(self.device.class(), self.device.appearance());
+		DeviceCategory::from_class_and_appearance(class.ok(), appearance.ok())
 	}
 
 	pub fn path(&self) -> zbus::zvariant::OwnedObjectPath {
 		self.device.inner().path().to_owned().into()
 	}
+
+	/// The service UUIDs offered by the device.
+	pub async fn services(&self) -> zbus::Result<Vec<BluetoothUuid>> {
+		Ok(BluetoothUuid::parse_all(&self.device.uuids().await?))
+	}
+
+	/// Connect a single profile of the device, such as [`ServiceClass::AudioSink`].
+	pub async fn connect_service(&self, uuid: impl Into<BluetoothUuid>) -> zbus::Result<()> {
+		self.device.connect_profile(&uuid.into().to_string()).await
+	}
+
+	/// Advertised manufacturer data, keyed by company identifier.
+	pub async fn manufacturer_data(&self) -> zbus::Result<HashMap<u16, Vec<u8>>> {
+		Ok(advertising::manufacturer_data(
+			self.device.manufacturer_data().await?,
+		))
+	}
+
+	/// Advertised service data, keyed by service UUID.
+	pub async fn service_data(&self) -> zbus::Result<HashMap<BluetoothUuid, Vec<u8>>> {
+		Ok(advertising::service_data(self.device.service_data().await?))
+	}
+
+	/// Advertising data of the types BlueZ does not decode itself, keyed by AD type.
+	pub async fn advertising_data(&self) -> zbus::Result<HashMap<u8, Vec<u8>>> {
+		Ok(advertising::advertising_data(
+			self.device.advertising_data().await?,
+		))
+	}
+
+	pub async fn disconnect_service(&self, uuid: impl Into<BluetoothUuid>) -> zbus::Result<()> {
+		self.device
+			.disconnect_profile(&uuid.into().to_string())
+			.await
+	}
 }
 
 pub async fn get_device<'a>(
@@ -137,27 +218,25 @@ pub async fn get_devices<'a>(
 		zbus::fdo::ObjectManagerProxy::new(connection, "org.bluez", "/").await?;
 	let managed_object: zbus::fdo::ManagedObjects =
 		managed_object_proxy.get_managed_objects().await?;
-	let device_addresses: Vec<zbus::zvariant::OwnedObjectPath> = managed_object
+	let device_addresses: Vec<(zbus::zvariant::OwnedObjectPath, bool)> = managed_object
 		.into_iter()
 		.filter_map(move |(path, interfaces)| {
-			if matches!(
-				adapter.map(|adapter| path.as_str().starts_with(&format!("{}/", adapter))),
-				None | Some(true)
-			) {
-				return interfaces
-					.contains_key("org.bluez.Device1")
-					.then_some(path.to_owned());
+			if is_on_adapter(&path, adapter) && interfaces.contains_key("org.bluez.Device1") {
+				let has_battery = interfaces.contains_key("org.bluez.Battery1");
+				return Some((path, has_battery));
 			}
 			None
 		})
 		.collect();
 	let devices: Vec<zbus::Result<(zbus::zvariant::OwnedObjectPath, BluetoothDevice<'a>)>> =
-		futures_util::future::join_all(device_addresses.into_iter().map(|path| async {
-			Ok((
-				path.clone(),
-				BluetoothDevice::new(connection, path.into()).await?,
-			))
-		}))
+		futures_util::future::join_all(device_addresses.into_iter().map(
+			|(path, has_battery)| async move {
+				Ok((
+					path.clone(),
+					BluetoothDevice::with_battery(connection, path.into(), has_battery).await?,
+				))
+			},
+		))
 		.await;
 
 	let errors = devices.iter().filter(|device| device.is_err());
@@ -178,3 +257,238 @@ pub async fn get_devices<'a>(
 		.filter_map(std::result::Result::ok)
 		.collect())
 }
+
+/// The properties of a device, decoded from `ObjectManager` data without
+/// building any proxies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceSnapshot {
+	pub path: zbus::zvariant::OwnedObjectPath,
+	pub adapter: Option<zbus::zvariant::OwnedObjectPath>,
+	pub address: String,
+	pub name: Option<String>,
+	pub alias: String,
+	pub icon: String,
+	pub class: Option<u32>,
+	pub appearance: Option<u16>,
+	pub paired: bool,
+	pub trusted: bool,
+	pub blocked: bool,
+	pub connected: bool,
+	pub rssi: Option<i16>,
+	pub uuids: Vec<String>,
+	/// Advertised manufacturer data, keyed by company identifier.
+	pub manufacturer_data: HashMap<u16, Vec<u8>>,
+	/// Advertised service data, keyed by service UUID.
+	pub service_data: HashMap<BluetoothUuid, Vec<u8>>,
+	/// The battery percentage, if the device exposes `org.bluez.Battery1`.
+	pub battery: Option<u8>,
+}
+
+impl DeviceSnapshot {
+	/// Decodes the interfaces of an object returned by `GetManagedObjects`,
+	/// returning `None` if it is not a device.
+	pub fn new(
+		path: zbus::zvariant::OwnedObjectPath,
+		mut interfaces: HashMap<
+			zbus::names::OwnedInterfaceName,
+			HashMap<String, zbus::zvariant::OwnedValue>,
+		>,
+	) -> Option<Self> {
+		let device = interfaces.remove("org.bluez.Device1")?;
+		let mut snapshot = Self {
+			path,
+			adapter: None,
+			address: String::new(),
+			name: None,
+			alias: String::new(),
+			icon: "unknown".to_owned(),
+			class: None,
+			appearance: None,
+			paired: false,
+			trusted: false,
+			blocked: false,
+			connected: false,
+			rssi: None,
+			uuids: Vec::new(),
+			manufacturer_data: HashMap::new(),
+			service_data: HashMap::new(),
+			battery: None,
+		};
+		snapshot.update_device(device, &[]);
+		if let Some(battery) = interfaces.remove("org.bluez.Battery1") {
+			snapshot.update_battery(battery);
+		}
+		if snapshot.alias.is_empty() {
+			snapshot.alias = snapshot.address.clone();
+		}
+		Some(snapshot)
+	}
+
+	/// Applies changed and invalidated properties of `org.bluez.Device1`.
+	pub fn update_device(
+		&mut self,
+		mut changed: HashMap<String, zbus::zvariant::OwnedValue>,
+		invalidated: &[String],
+	) {
+		let properties = &mut changed;
+		update_option(properties, "Adapter", &mut self.adapter);
+		update(properties, "Address", &mut self.address);
+		update_option(properties, "Name", &mut self.name);
+		update(properties, "Alias", &mut self.alias);
+		update(properties, "Icon", &mut self.icon);
+		update_option(properties, "Class", &mut self.class);
+		update_option(properties, "Appearance", &mut self.appearance);
+		update(properties, "Paired", &mut self.paired);
+		update(properties, "Trusted", &mut self.trusted);
+		update(properties, "Blocked", &mut self.blocked);
+		update(properties, "Connected", &mut self.connected);
+		update_option(properties, "RSSI", &mut self.rssi);
+		update(properties, "UUIDs", &mut self.uuids);
+		if let Some(data) = take_property(properties, "ManufacturerData") {
+			self.manufacturer_data = advertising::manufacturer_data(data);
+		}
+		if let Some(data) = take_property(properties, "ServiceData") {
+			self.service_data = advertising::service_data(data);
+		}
+		for name in invalidated {
+			match name.as_str() {
+				"Name" => self.name = None,
+				"Class" => self.class = None,
+				"Appearance" => self.appearance = None,
+				"RSSI" => self.rssi = None,
+				"ManufacturerData" => self.manufacturer_data.clear(),
+				"ServiceData" => self.service_data.clear(),
+				_ => (),
+			}
+		}
+	}
+
+	/// Applies changed properties of `org.bluez.Battery1`.
+	pub fn update_battery(&mut self, mut changed: HashMap<String, zbus::zvariant::OwnedValue>) {
+		update_option(&mut changed, "Percentage", &mut self.battery);
+	}
+
+	pub fn category(&self) -> DeviceCategory {
+		DeviceCategory::from_class_and_appearance(self.class, self.appearance)
+	}
+
+	/// The icon BlueZ reports, or one derived from the class or appearance of
+	/// the device if BlueZ omits it.
+	pub fn icon_name(&self) -> &str {
+		if self.icon.is_empty() || self.icon == "unknown" {
+			self.category().icon_name()
+		} else {
+			&self.icon
+		}
+	}
+
+	/// The name a device advertises for Swift Pair, which some devices send
+	/// before BlueZ learns their `Name`.
+	pub fn advertised_name(&self) -> Option<String> {
+		let data = self
+			.manufacturer_data
+			.get(&advertising::COMPANY_MICROSOFT)?;
+		advertising::SwiftPair::parse(data)?.display_name
+	}
+
+	/// The battery level reported by `org.bluez.Battery1`, or else one decoded
+	/// from advertised manufacturer data.
+	pub fn battery_level(&self) -> Option<u8> {
+		self.battery.or_else(|| {
+			let data = self.manufacturer_data.get(&advertising::COMPANY_APPLE)?;
+			advertising::AppleBatteryStatus::parse(data)?.level()
+		})
+	}
+
+	pub fn service_uuids(&self) -> Vec<BluetoothUuid> {
+		BluetoothUuid::parse_all(&self.uuids)
+	}
+
+	/// The well-known services offered by the device, in the order BlueZ lists
+	/// them. Unknown and vendor-specific UUIDs are omitted.
+	pub fn service_classes(&self) -> Vec<ServiceClass> {
+		self.service_uuids()
+			.into_iter()
+			.filter_map(BluetoothUuid::service_class)
+			.collect()
+	}
+
+	/// A comma-separated list of the names of the device's well-known services,
+	/// such as "Headset, Audio Sink".
+	pub fn service_names(&self) -> String {
+		let names: Vec<&str> = self
+			.service_classes()
+			.into_iter()
+			.map(ServiceClass::name)
+			.collect();
+		names.join(", ")
+	}
+}
+
+/// Fetches every device, optionally only those of `adapter`, with a single
+/// `GetManagedObjects` call.
+pub async fn get_device_snapshots(
+	connection: &zbus::Connection,
+	adapter: Option<&str>,
+) -> zbus::Result<Vec<DeviceSnapshot>> {
+	let managed_object_proxy =
+		zbus::fdo::ObjectManagerProxy::new(connection, "org.bluez", "/").await?;
+	let managed_object: zbus::fdo::ManagedObjects =
+		managed_object_proxy.get_managed_objects().await?;
+	Ok(managed_object
+		.into_iter()
+		.filter(|(path, _)| is_on_adapter(path, adapter))
+		.filter_map(|(path, interfaces)| DeviceSnapshot::new(path, interfaces))
+		.collect())
+}
+
+fn is_on_adapter(path: &zbus::zvariant::OwnedObjectPath, adapter: Option<&str>) -> bool {
+	adapter.is_none_or(|adapter| path.as_str().starts_with(&format!("{}/", adapter)))
+}
+
+pub(crate) fn update<T: TryFrom<zbus::zvariant::OwnedValue>>(
+	properties: &mut HashMap<String, zbus::zvariant::OwnedValue>,
+	name: &str,
+	field: &mut T,
+) {
+	if let Some(value) = take_property(properties, name) {
+		*field = value;
+	}
+}
+
+fn update_option<T: TryFrom<zbus::zvariant::OwnedValue>>(
+	properties: &mut HashMap<String, zbus::zvariant::OwnedValue>,
+	name: &str,
+	field: &mut Option<T>,
+) {
+	if let Some(value) = take_property(properties, name) {
+		*field = Some(value);
+	}
+}
+
+pub(crate) fn take_property<T: TryFrom<zbus::zvariant::OwnedValue>>(
+	properties: &mut HashMap<String, zbus::zvariant::OwnedValue>,
+	name: &str,
+) -> Option<T> {
+	properties
+		.remove(name)
+		.and_then(|value| value.try_into().ok())
+}
+
+pub(crate) fn owned_interfaces(
+	interfaces: &HashMap<zbus::names::InterfaceName<'_>, HashMap<&str, zbus::zvariant::Value<'_>>>,
+) -> HashMap<zbus::names::OwnedInterfaceName, HashMap<String, zbus::zvariant::OwnedValue>> {
+	interfaces
+		.iter()
+		.map(|(name, properties)| (name.to_owned().into(), owned_properties(properties)))
+		.collect()
+}
+
+pub(crate) fn owned_properties(
+	properties: &HashMap<&str, zbus::zvariant::Value<'_>>,
+) -> HashMap<String, zbus::zvariant::OwnedValue> {
+	properties
+		.iter()
+		.filter_map(|(name, value)| Some(((*name).to_owned(), value.try_to_owned().ok()?)))
+		.collect()
+}
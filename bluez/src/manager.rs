@@ -0,0 +1,346 @@
+// Copyright 2024 System76 <info@system76.com>
+// SPDX-License-Identifier: MPL-2.0
+
+//! An in-memory view of the BlueZ object tree, kept up to date from signals.
+
+use std::{
+	collections::{HashMap, VecDeque},
+	pin::Pin,
+};
+
+use futures_util::{Stream, StreamExt};
+use zbus::{
+	names::OwnedInterfaceName,
+	zvariant::{OwnedObjectPath, OwnedValue},
+};
+
+use crate::{DeviceSnapshot, owned_interfaces, owned_properties, take_property, update};
+
+const ADAPTER_INTERFACE: &str = "org.bluez.Adapter1";
+const DEVICE_INTERFACE: &str = "org.bluez.Device1";
+const BATTERY_INTERFACE: &str = "org.bluez.Battery1";
+
+/// The properties of an adapter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdapterSnapshot {
+	pub path: OwnedObjectPath,
+	pub address: String,
+	pub name: String,
+	pub alias: String,
+	pub powered: bool,
+	pub discoverable: bool,
+	pub pairable: bool,
+	pub discovering: bool,
+}
+
+impl AdapterSnapshot {
+	fn new(path: OwnedObjectPath, properties: HashMap<String, OwnedValue>) -> Self {
+		let mut snapshot = Self {
+			path,
+			address: String::new(),
+			name: String::new(),
+			alias: String::new(),
+			powered: false,
+			discoverable: false,
+			pairable: false,
+			discovering: false,
+		};
+		snapshot.update(properties);
+		snapshot
+	}
+
+	/// Applies changed properties of `org.bluez.Adapter1`.
+	pub fn update(&mut self, mut changed: HashMap<String, OwnedValue>) {
+		let properties = &mut changed;
+		update(properties, "Address", &mut self.address);
+		update(properties, "Name", &mut self.name);
+		update(properties, "Alias", &mut self.alias);
+		update(properties, "Powered", &mut self.powered);
+		update(properties, "Discoverable", &mut self.discoverable);
+		update(properties, "Pairable", &mut self.pairable);
+		update(properties, "Discovering", &mut self.discovering);
+	}
+}
+
+/// A change to the BlueZ object tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BluezEvent {
+	AdapterAdded(AdapterSnapshot),
+	AdapterRemoved(OwnedObjectPath),
+	AdapterPowered {
+		path: OwnedObjectPath,
+		powered: bool,
+	},
+	/// Properties of an adapter other than `Powered` changed.
+	AdapterChanged(AdapterSnapshot),
+	DeviceDiscovered(DeviceSnapshot),
+	DeviceRemoved(OwnedObjectPath),
+	DeviceConnected {
+		path: OwnedObjectPath,
+		connected: bool,
+	},
+	/// Properties of a device other than `Connected` changed.
+	DeviceChanged(DeviceSnapshot),
+	/// The battery level of a device changed, or the device stopped reporting it.
+	BatteryChanged {
+		path: OwnedObjectPath,
+		percentage: Option<u8>,
+	},
+}
+
+type Interfaces = HashMap<OwnedInterfaceName, HashMap<String, OwnedValue>>;
+
+pub(crate) enum Signal {
+	InterfacesAdded(OwnedObjectPath, Interfaces),
+	InterfacesRemoved(OwnedObjectPath, Vec<String>),
+	PropertiesChanged {
+		path: OwnedObjectPath,
+		interface: String,
+		changed: HashMap<String, OwnedValue>,
+		invalidated: Vec<String>,
+	},
+}
+
+/// The object tree signals of BlueZ. A single match rule keeps them in bus
+/// order, so that a device's properties never change before it is added, or
+/// after it is removed.
+pub(crate) async fn signals(
+	connection: &zbus::Connection,
+) -> zbus::Result<impl Stream<Item = Signal> + use<>> {
+	let rule = zbus::MatchRule::builder()
+		.msg_type(zbus::message::Type::Signal)
+		.sender("org.bluez")?
+		.build();
+	Ok(zbus::MessageStream::for_match_rule(rule, connection, None)
+		.await?
+		.filter_map(|message| async move { Signal::from_message(message.ok()?) }))
+}
+
+impl Signal {
+	fn from_message(message: zbus::Message) -> Option<Self> {
+		let header = message.header();
+		let interface = header.interface()?.to_string();
+		let member = header.member()?.to_string();
+		match (interface.as_str(), member.as_str()) {
+			("org.freedesktop.DBus.ObjectManager", "InterfacesAdded") => {
+				let signal = zbus::fdo::InterfacesAdded::from_message(message)?;
+				let args = signal.args().ok()?;
+				Some(Self::InterfacesAdded(
+					args.object_path.to_owned().into(),
+					owned_interfaces(&args.interfaces_and_properties),
+				))
+			}
+			("org.freedesktop.DBus.ObjectManager", "InterfacesRemoved") => {
+				let signal = zbus::fdo::InterfacesRemoved::from_message(message)?;
+				let args = signal.args().ok()?;
+				Some(Self::InterfacesRemoved(
+					args.object_path.to_owned().into(),
+					args.interfaces
+						.iter()
+						.map(|name| name.to_string())
+						.collect(),
+				))
+			}
+			("org.freedesktop.DBus.Properties", "PropertiesChanged") => {
+				let path = header.path()?.to_owned().into();
+				let signal = zbus::fdo::PropertiesChanged::from_message(message)?;
+				let args = signal.args().ok()?;
+				Some(Self::PropertiesChanged {
+					path,
+					interface: args.interface_name.to_string(),
+					changed: owned_properties(&args.changed_properties),
+					invalidated: args
+						.invalidated_properties
+						.iter()
+						.map(|name| (*name).to_owned())
+						.collect(),
+				})
+			}
+			_ => None,
+		}
+	}
+}
+
+/// Tracks the adapters and devices of BlueZ, including their batteries.
+///
+/// The manager subscribes to the signals of BlueZ before reading the initial
+/// state, so no change is missed, and applies them in the order they were sent.
+/// Its state is updated as events are read with [`Self::next_event`].
+pub struct BluezManager {
+	adapters: HashMap<OwnedObjectPath, AdapterSnapshot>,
+	devices: HashMap<OwnedObjectPath, DeviceSnapshot>,
+	pending: VecDeque<BluezEvent>,
+	signals: Pin<Box<dyn Stream<Item = Signal> + Send>>,
+}
+
+impl std::fmt::Debug for BluezManager {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("BluezManager")
+			.field("adapters", &self.adapters)
+			.field("devices", &self.devices)
+			.finish_non_exhaustive()
+	}
+}
+
+impl BluezManager {
+	pub async fn new(connection: &zbus::Connection) -> zbus::Result<Self> {
+		let signals = signals(connection).await?;
+		let object_manager =
+			zbus::fdo::ObjectManagerProxy::new(connection, "org.bluez", "/").await?;
+
+		let mut manager = Self {
+			adapters: HashMap::new(),
+			devices: HashMap::new(),
+			pending: VecDeque::new(),
+			signals: Box::pin(signals),
+		};
+		for (path, mut interfaces) in object_manager.get_managed_objects().await? {
+			if let Some(properties) = interfaces.remove(ADAPTER_INTERFACE) {
+				let adapter = AdapterSnapshot::new(path.clone(), properties);
+				manager.adapters.insert(path.clone(), adapter);
+			}
+			if let Some(device) = DeviceSnapshot::new(path.clone(), interfaces) {
+				manager.devices.insert(path, device);
+			}
+		}
+		Ok(manager)
+	}
+
+	pub fn adapters(&self) -> impl Iterator<Item = &AdapterSnapshot> {
+		self.adapters.values()
+	}
+
+	pub fn adapter(&self, path: &OwnedObjectPath) -> Option<&AdapterSnapshot> {
+		self.adapters.get(path)
+	}
+
+	pub fn devices(&self) -> impl Iterator<Item = &DeviceSnapshot> {
+		self.devices.values()
+	}
+
+	pub fn device(&self, path: &OwnedObjectPath) -> Option<&DeviceSnapshot> {
+		self.devices.get(path)
+	}
+
+	/// Wait for the next change, applying it to the tracked state. Returns
+	/// `None` once the connection to the bus is closed.
+	pub async fn next_event(&mut self) -> Option<BluezEvent> {
+		loop {
+			if let Some(event) = self.pending.pop_front() {
+				return Some(event);
+			}
+			let signal = self.signals.next().await?;
+			self.apply(signal);
+		}
+	}
+
+	/// Turn the manager into a stream of events.
+	pub fn into_events(self) -> impl Stream<Item = BluezEvent> {
+		futures_util::stream::unfold(self, |mut manager| async move {
+			let event = manager.next_event().await?;
+			Some((event, manager))
+		})
+	}
+
+	fn apply(&mut self, signal: Signal) {
+		match signal {
+			Signal::InterfacesAdded(path, mut interfaces) => {
+				if let Some(properties) = interfaces.remove(ADAPTER_INTERFACE) {
+					let adapter = AdapterSnapshot::new(path.clone(), properties);
+					self.adapters.insert(path.clone(), adapter.clone());
+					self.pending.push_back(BluezEvent::AdapterAdded(adapter));
+				}
+				if interfaces.contains_key(DEVICE_INTERFACE) {
+					if let Some(device) = DeviceSnapshot::new(path.clone(), interfaces) {
+						self.devices.insert(path, device.clone());
+						self.pending.push_back(BluezEvent::DeviceDiscovered(device));
+					}
+				} else if let Some(properties) = interfaces.remove(BATTERY_INTERFACE)
+					&& let Some(device) = self.devices.get_mut(&path)
+				{
+					device.update_battery(properties);
+					self.pending.push_back(BluezEvent::BatteryChanged {
+						path,
+						percentage: device.battery,
+					});
+				}
+			}
+
+			Signal::InterfacesRemoved(path, interfaces) => {
+				for interface in interfaces {
+					let event = match interface.as_str() {
+						ADAPTER_INTERFACE => self
+							.adapters
+							.remove(&path)
+							.map(|_| BluezEvent::AdapterRemoved(path.clone())),
+						DEVICE_INTERFACE => self
+							.devices
+							.remove(&path)
+							.map(|_| BluezEvent::DeviceRemoved(path.clone())),
+						BATTERY_INTERFACE => self.devices.get_mut(&path).map(|device| {
+							device.battery = None;
+							BluezEvent::BatteryChanged {
+								path: path.clone(),
+								percentage: None,
+							}
+						}),
+						_ => None,
+					};
+					self.pending.extend(event);
+				}
+			}
+
+			Signal::PropertiesChanged {
+				path,
+				interface,
+				mut changed,
+				invalidated,
+			} => match interface.as_str() {
+				ADAPTER_INTERFACE => {
+					let Some(adapter) = self.adapters.get_mut(&path) else {
+						return;
+					};
+					let powered = take_property::<bool>(&mut changed, "Powered");
+					let other_changes = !changed.is_empty();
+					adapter.update(changed);
+					if let Some(powered) = powered {
+						adapter.powered = powered;
+						self.pending
+							.push_back(BluezEvent::AdapterPowered { path, powered });
+					}
+					if other_changes {
+						self.pending
+							.push_back(BluezEvent::AdapterChanged(adapter.clone()));
+					}
+				}
+				DEVICE_INTERFACE => {
+					let Some(device) = self.devices.get_mut(&path) else {
+						return;
+					};
+					let connected = take_property::<bool>(&mut changed, "Connected");
+					let other_changes = !changed.is_empty() || !invalidated.is_empty();
+					device.update_device(changed, &invalidated);
+					if let Some(connected) = connected {
+						device.connected = connected;
+						self.pending
+							.push_back(BluezEvent::DeviceConnected { path, connected });
+					}
+					if other_changes {
+						self.pending
+							.push_back(BluezEvent::DeviceChanged(device.clone()));
+					}
+				}
+				BATTERY_INTERFACE => {
+					if let Some(device) = self.devices.get_mut(&path) {
+						device.update_battery(changed);
+						self.pending.push_back(BluezEvent::BatteryChanged {
+							path,
+							percentage: device.battery,
+						});
+					}
+				}
+				_ => (),
+			},
+		}
+	}
+}
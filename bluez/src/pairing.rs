@@ -0,0 +1,380 @@
+// Copyright 2024 System76 <info@system76.com>
+// SPDX-License-Identifier: MPL-2.0
+
+//! Pairing a device while answering its agent requests.
+
+use std::{
+	future::Future,
+	sync::atomic::{AtomicU32, Ordering},
+};
+
+use futures_util::{StreamExt, future::Either};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath};
+
+use crate::{
+	BluetoothDevice,
+	agent_manager1::AgentManager1Proxy,
+	agent1::{self, Capability, Message},
+};
+
+/// Answers the agent requests BlueZ makes while pairing a device. The default
+/// implementations reject every request.
+pub trait PairingHandler: Send {
+	/// Return the PIN code to enter on a legacy device, or `None` to reject.
+	fn request_pin_code(&mut self) -> impl Future<Output = Option<String>> + Send {
+		async { None }
+	}
+
+	/// Return the passkey shown on the remote device, or `None` to reject.
+	fn request_passkey(&mut self) -> impl Future<Output = Option<u32>> + Send {
+		async { None }
+	}
+
+	/// Show a passkey to be typed on the remote device, of which `entered`
+	/// digits have been typed so far.
+	fn display_passkey(&mut self, passkey: u32, entered: u16) -> impl Future<Output = ()> + Send {
+		_ = (passkey, entered);
+		async {}
+	}
+
+	/// Show a PIN code to be typed on the remote device.
+	fn display_pin_code(&mut self, pin_code: String) -> impl Future<Output = ()> + Send {
+		_ = pin_code;
+		async {}
+	}
+
+	/// Confirm that the remote device shows the same passkey.
+	fn request_confirmation(&mut self, passkey: u32) -> impl Future<Output = bool> + Send {
+		_ = passkey;
+		async { false }
+	}
+
+	/// Authorize pairing without a passkey ("just works" pairing).
+	fn request_authorization(&mut self) -> impl Future<Output = bool> + Send {
+		async { false }
+	}
+
+	/// Authorize the device to connect to the service with the given UUID.
+	fn authorize_service(&mut self, uuid: String) -> impl Future<Output = bool> + Send {
+		_ = uuid;
+		async { false }
+	}
+
+	/// A previous request was canceled by BlueZ and should no longer be shown.
+	fn cancel(&mut self) -> impl Future<Output = ()> + Send {
+		async {}
+	}
+}
+
+/// What to do around [`BluetoothDevice::pair_with`].
+#[derive(Clone, Copy, Debug)]
+pub struct PairingOptions {
+	/// The input and output capabilities announced by the agent.
+	pub capability: Capability,
+	/// Mark the device as trusted once paired.
+	pub trust: bool,
+	/// Connect to the device once paired.
+	pub connect: bool,
+	/// Make the agent the default agent while pairing, so that it also receives
+	/// the requests the device makes on its own. BlueZ restores the previous
+	/// default agent once it is unregistered.
+	pub default_agent: bool,
+}
+
+impl Default for PairingOptions {
+	fn default() -> Self {
+		Self {
+			capability: Capability::KeyboardDisplay,
+			trust: true,
+			connect: true,
+			default_agent: true,
+		}
+	}
+}
+
+/// Why pairing failed.
+#[derive(Debug)]
+pub enum PairingError {
+	AuthenticationFailed,
+	AuthenticationCanceled,
+	AuthenticationRejected,
+	AuthenticationTimeout,
+	/// The device is already paired.
+	AlreadyExists,
+	ConnectionAttemptFailed,
+	InProgress,
+	/// The connection already has an agent registered, which would receive the
+	/// requests of the pairing instead of the handler.
+	AgentAlreadyRegistered,
+	Dbus(zbus::Error),
+}
+
+impl From<zbus::Error> for PairingError {
+	fn from(why: zbus::Error) -> Self {
+		let zbus::Error::MethodError(name, _, _) = &why else {
+			return Self::Dbus(why);
+		};
+		match name.as_str() {
+			"org.bluez.Error.AuthenticationFailed" => Self::AuthenticationFailed,
+			"org.bluez.Error.AuthenticationCanceled" => Self::AuthenticationCanceled,
+			"org.bluez.Error.AuthenticationRejected" => Self::AuthenticationRejected,
+			"org.bluez.Error.AuthenticationTimeout" => Self::AuthenticationTimeout,
+			"org.bluez.Error.AlreadyExists" => Self::AlreadyExists,
+			"org.bluez.Error.ConnectionAttemptFailed" => Self::ConnectionAttemptFailed,
+			"org.bluez.Error.InProgress" => Self::InProgress,
+			_ => Self::Dbus(why),
+		}
+	}
+}
+
+impl std::fmt::Display for PairingError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::AuthenticationFailed => f.write_str("authentication failed"),
+			Self::AuthenticationCanceled => f.write_str("authentication canceled"),
+			Self::AuthenticationRejected => f.write_str("authentication rejected"),
+			Self::AuthenticationTimeout => f.write_str("authentication timed out"),
+			Self::AlreadyExists => f.write_str("device is already paired"),
+			Self::ConnectionAttemptFailed => f.write_str("connection attempt failed"),
+			Self::InProgress => f.write_str("pairing is already in progress"),
+			Self::AgentAlreadyRegistered => {
+				f.write_str("an agent is already registered on the connection")
+			}
+			Self::Dbus(why) => write!(f, "D-Bus error: {why}"),
+		}
+	}
+}
+
+impl std::error::Error for PairingError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Dbus(why) => Some(why),
+			_ => None,
+		}
+	}
+}
+
+static AGENT_ID: AtomicU32 = AtomicU32::new(0);
+
+/// The agent of [`BluetoothDevice::pair_with`]. Dropping it before it is
+/// released, as when the pairing future is dropped, cancels the pairing and
+/// unregisters and unexports the agent in the background.
+struct PairingAgent {
+	connection: zbus::Connection,
+	agent_path: OwnedObjectPath,
+	device_path: OwnedObjectPath,
+	registered: bool,
+	pairing: bool,
+	released: bool,
+}
+
+impl PairingAgent {
+	async fn release(mut self) {
+		self.released = true;
+		release_agent(
+			self.connection.clone(),
+			self.agent_path.clone(),
+			self.device_path.clone(),
+			self.registered,
+			self.pairing,
+		)
+		.await;
+	}
+}
+
+impl Drop for PairingAgent {
+	fn drop(&mut self) {
+		if self.released {
+			return;
+		}
+		let release = release_agent(
+			self.connection.clone(),
+			self.agent_path.clone(),
+			self.device_path.clone(),
+			self.registered,
+			self.pairing,
+		);
+		self.connection
+			.executor()
+			.spawn(release, "bluez-release-pairing-agent")
+			.detach();
+	}
+}
+
+async fn release_agent(
+	connection: zbus::Connection,
+	agent_path: OwnedObjectPath,
+	device_path: OwnedObjectPath,
+	registered: bool,
+	pairing: bool,
+) {
+	if pairing {
+		let result = connection
+			.call_method(
+				Some("org.bluez"),
+				&device_path,
+				Some("org.bluez.Device1"),
+				"CancelPairing",
+				&(),
+			)
+			.await;
+		if let Err(why) = result {
+			tracing::warn!("failed to cancel pairing: {why}");
+		}
+	}
+	if registered {
+		let result = connection
+			.call_method(
+				Some("org.bluez"),
+				"/org/bluez",
+				Some("org.bluez.AgentManager1"),
+				"UnregisterAgent",
+				&(&agent_path,),
+			)
+			.await;
+		if let Err(why) = result {
+			tracing::warn!("failed to unregister pairing agent: {why}");
+		}
+	}
+	_ = connection
+		.object_server()
+		.remove::<agent1::Agent, _>(&agent_path)
+		.await;
+}
+
+impl BluetoothDevice<'_> {
+	/// Pair with the device, answering its agent requests with `handler`.
+	///
+	/// An agent is registered on the device's connection for the duration of the
+	/// call, and made the default agent if [`PairingOptions::default_agent`] is
+	/// set. BlueZ sends the requests of a pairing to the agent of the client
+	/// that started it, so if the connection already has an agent registered
+	/// this fails with [`PairingError::AgentAlreadyRegistered`]. Dropping the
+	/// returned future cancels the pairing and unregisters the agent.
+	pub async fn pair_with(
+		&self,
+		handler: &mut impl PairingHandler,
+		options: PairingOptions,
+	) -> Result<(), PairingError> {
+		let connection = self.device.inner().connection();
+		let agent_path = ObjectPath::try_from(format!(
+			"/org/bluez/agent/pairing{}",
+			AGENT_ID.fetch_add(1, Ordering::Relaxed)
+		))
+		.map_err(zbus::Error::from)?;
+		let (agent, mut messages) = agent1::create();
+		connection.object_server().at(&agent_path, agent).await?;
+		let mut pairing_agent = PairingAgent {
+			connection: connection.clone(),
+			agent_path: agent_path.clone().into(),
+			device_path: self.path(),
+			registered: false,
+			pairing: false,
+			released: false,
+		};
+
+		let agent_manager = AgentManager1Proxy::new(connection).await?;
+		match agent_manager
+			.register_agent(&agent_path, options.capability.into())
+			.await
+		{
+			Ok(()) => pairing_agent.registered = true,
+			Err(zbus::Error::MethodError(name, _, _))
+				if name.as_str() == "org.bluez.Error.AlreadyExists" =>
+			{
+				return Err(PairingError::AgentAlreadyRegistered);
+			}
+			Err(why) => return Err(why.into()),
+		}
+		if options.default_agent {
+			agent_manager.request_default_agent(&agent_path).await?;
+		}
+
+		let device_path = pairing_agent.device_path.clone();
+		let pair = self.device.pair();
+		futures_util::pin_mut!(pair);
+		pairing_agent.pairing = true;
+		let result = loop {
+			match futures_util::future::select(&mut pair, messages.next()).await {
+				Either::Left((result, _)) => break result,
+				Either::Right((Some(message), _)) => {
+					handle_message(handler, device_path.as_str(), message).await;
+				}
+				Either::Right((None, _)) => break (&mut pair).await,
+			}
+		};
+		pairing_agent.pairing = false;
+		pairing_agent.release().await;
+		result?;
+
+		if options.trust {
+			self.device.set_trusted(true).await?;
+		}
+		if options.connect {
+			self.device.connect().await?;
+		}
+		Ok(())
+	}
+}
+
+/// Route an agent request to `handler` if it concerns `device`, rejecting it otherwise.
+async fn handle_message(handler: &mut impl PairingHandler, device: &str, message: Message) {
+	match message {
+		Message::RequestPinCode {
+			device: from,
+			response,
+		} => {
+			let pin_code = if from.as_str() == device {
+				handler.request_pin_code().await
+			} else {
+				None
+			};
+			_ = response.send(pin_code);
+		}
+		Message::RequestPasskey {
+			device: from,
+			response,
+		} => {
+			let passkey = if from.as_str() == device {
+				handler.request_passkey().await
+			} else {
+				None
+			};
+			_ = response.send(passkey);
+		}
+		Message::RequestConfirmation {
+			device: from,
+			passkey,
+			response,
+		} => {
+			let confirmed = from.as_str() == device && handler.request_confirmation(passkey).await;
+			_ = response.send(confirmed);
+		}
+		Message::RequestAuthorization {
+			device: from,
+			response,
+		} => {
+			let authorized = from.as_str() == device && handler.request_authorization().await;
+			_ = response.send(authorized);
+		}
+		Message::DisplayPasskey {
+			device: from,
+			passkey,
+			entered,
+		} if from.as_str() == device => handler.display_passkey(passkey, entered).await,
+		Message::DisplayPinCode {
+			device: from,
+			pincode,
+		} if from.as_str() == device => handler.display_pin_code(pincode).await,
+		Message::AuthorizeService {
+			device: from,
+			uuid,
+			response,
+		} => {
+			let authorized = from.as_str() == device && handler.authorize_service(uuid).await;
+			_ = response.send(authorized);
+		}
+		Message::Cancel => handler.cancel().await,
+		_ => (),
+	}
+}
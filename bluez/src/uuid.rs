@@ -0,0 +1,222 @@
+// Copyright 2024 System76 <info@system76.com>
+// SPDX-License-Identifier: MPL-2.0
+
+//! Bluetooth UUIDs and the assigned numbers of well-known service classes.
+
+use std::{fmt, str::FromStr};
+
+/// A Bluetooth UUID. Short 16-bit and 32-bit UUIDs are offsets into the
+/// Bluetooth base UUID `00000000-0000-1000-8000-00805f9b34fb`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BluetoothUuid(pub u128);
+
+impl BluetoothUuid {
+	pub const BASE: BluetoothUuid = BluetoothUuid(0x0000_0000_0000_1000_8000_0080_5f9b_34fb);
+
+	pub const fn from_u16(uuid: u16) -> Self {
+		Self::from_u32(uuid as u32)
+	}
+
+	pub const fn from_u32(uuid: u32) -> Self {
+		Self(Self::BASE.0 | ((uuid as u128) << 96))
+	}
+
+	/// The 32-bit short form, if the UUID is derived from the base UUID.
+	pub const fn as_u32(self) -> Option<u32> {
+		if self.0 & ((1 << 96) - 1) == Self::BASE.0 {
+			Some((self.0 >> 96) as u32)
+		} else {
+			None
+		}
+	}
+
+	/// The 16-bit short form, if the UUID is derived from the base UUID.
+	pub const fn as_u16(self) -> Option<u16> {
+		match self.as_u32() {
+			Some(uuid) if uuid <= u16::MAX as u32 => Some(uuid as u16),
+			_ => None,
+		}
+	}
+
+	/// Parses UUIDs as returned by the `UUIDs` property of devices and adapters,
+	/// skipping any that are malformed.
+	pub fn parse_all(uuids: &[impl AsRef<str>]) -> Vec<Self> {
+		uuids
+			.iter()
+			.filter_map(|uuid| uuid.as_ref().parse().ok())
+			.collect()
+	}
+
+	/// The well-known service class identified by this UUID.
+	pub fn service_class(self) -> Option<ServiceClass> {
+		ServiceClass::from_uuid(self)
+	}
+}
+
+impl From<u16> for BluetoothUuid {
+	fn from(uuid: u16) -> Self {
+		Self::from_u16(uuid)
+	}
+}
+
+impl From<u32> for BluetoothUuid {
+	fn from(uuid: u32) -> Self {
+		Self::from_u32(uuid)
+	}
+}
+
+impl From<u128> for BluetoothUuid {
+	fn from(uuid: u128) -> Self {
+		Self(uuid)
+	}
+}
+
+/// Formats the UUID in its full, lowercase 128-bit form as used by BlueZ.
+impl fmt::Display for BluetoothUuid {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let uuid = self.0;
+		write!(
+			f,
+			"{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+			uuid >> 96,
+			(uuid >> 80) & 0xffff,
+			(uuid >> 64) & 0xffff,
+			(uuid >> 48) & 0xffff,
+			uuid & 0xffff_ffff_ffff
+		)
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseUuidError;
+
+impl fmt::Display for ParseUuidError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("invalid Bluetooth UUID")
+	}
+}
+
+impl std::error::Error for ParseUuidError {}
+
+/// Parses the 16-bit (`110b`), 32-bit (`0000110b`) and 128-bit
+/// (`0000110b-0000-1000-8000-00805f9b34fb`) forms, with an optional `0x` prefix
+/// on the short forms.
+impl FromStr for BluetoothUuid {
+	type Err = ParseUuidError;
+
+	fn from_str(uuid: &str) -> Result<Self, Self::Err> {
+		let short = uuid
+			.strip_prefix("0x")
+			.or_else(|| uuid.strip_prefix("0X"))
+			.unwrap_or(uuid);
+		let parse = |digits: &str| {
+			if digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+				u128::from_str_radix(digits, 16).map_err(|_| ParseUuidError)
+			} else {
+				Err(ParseUuidError)
+			}
+		};
+		match short.len() {
+			4 | 8 => parse(short).map(|short| Self::from_u32(short as u32)),
+			_ => {
+				let groups: Vec<&str> = uuid.split('-').collect();
+				let lengths = groups.iter().map(|group| group.len());
+				if !lengths.eq([8, 4, 4, 4, 12]) {
+					return Err(ParseUuidError);
+				}
+				parse(&groups.concat()).map(Self)
+			}
+		}
+	}
+}
+
+macro_rules! service_classes {
+	($($variant:ident = $uuid:literal, $name:literal;)*) => {
+		/// Well-known Bluetooth service classes and GATT services.
+		#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+		pub enum ServiceClass {
+			$($variant,)*
+		}
+
+		impl ServiceClass {
+			pub fn from_uuid(uuid: BluetoothUuid) -> Option<Self> {
+				match uuid.as_u16()? {
+					$($uuid => Some(Self::$variant),)*
+					_ => None,
+				}
+			}
+
+			pub fn uuid(self) -> BluetoothUuid {
+				match self {
+					$(Self::$variant => BluetoothUuid::from_u16($uuid),)*
+				}
+			}
+
+			/// A human-readable name for the service.
+			pub fn name(self) -> &'static str {
+				match self {
+					$(Self::$variant => $name,)*
+				}
+			}
+		}
+	};
+}
+
+service_classes! {
+	SerialPort = 0x1101, "Serial Port";
+	DialupNetworking = 0x1103, "Dial-up Networking";
+	ObexObjectPush = 0x1105, "OBEX Object Push";
+	ObexFileTransfer = 0x1106, "OBEX File Transfer";
+	Headset = 0x1108, "Headset";
+	AudioSource = 0x110a, "Audio Source";
+	AudioSink = 0x110b, "Audio Sink";
+	AvRemoteControlTarget = 0x110c, "A/V Remote Control Target";
+	AdvancedAudioDistribution = 0x110d, "Advanced Audio Distribution";
+	AvRemoteControl = 0x110e, "A/V Remote Control";
+	AvRemoteControlController = 0x110f, "A/V Remote Control Controller";
+	HeadsetAudioGateway = 0x1112, "Headset Audio Gateway";
+	PanUser = 0x1115, "PAN User";
+	NetworkAccessPoint = 0x1116, "Network Access Point";
+	GroupNetwork = 0x1117, "Group Ad-hoc Network";
+	Handsfree = 0x111e, "Handsfree";
+	HandsfreeAudioGateway = 0x111f, "Handsfree Audio Gateway";
+	HumanInterfaceDevice = 0x1124, "Human Interface Device";
+	SimAccess = 0x112d, "SIM Access";
+	PhonebookAccessClient = 0x112e, "Phonebook Access Client";
+	PhonebookAccessServer = 0x112f, "Phonebook Access Server";
+	MessageAccessServer = 0x1132, "Message Access Server";
+	MessageNotificationServer = 0x1133, "Message Notification Server";
+	PnpInformation = 0x1200, "PnP Information";
+	GenericAccess = 0x1800, "Generic Access";
+	GenericAttribute = 0x1801, "Generic Attribute";
+	ImmediateAlert = 0x1802, "Immediate Alert";
+	LinkLoss = 0x1803, "Link Loss";
+	TxPower = 0x1804, "Tx Power";
+	CurrentTime = 0x1805, "Current Time";
+	HealthThermometer = 0x1809, "Health Thermometer";
+	DeviceInformation = 0x180a, "Device Information";
+	HeartRate = 0x180d, "Heart Rate";
+	Battery = 0x180f, "Battery";
+	BloodPressure = 0x1810, "Blood Pressure";
+	HidOverGatt = 0x1812, "HID over GATT";
+	ScanParameters = 0x1813, "Scan Parameters";
+	RunningSpeedAndCadence = 0x1814, "Running Speed and Cadence";
+	CyclingSpeedAndCadence = 0x1816, "Cycling Speed and Cadence";
+	CyclingPower = 0x1818, "Cycling Power";
+	EnvironmentalSensing = 0x181a, "Environmental Sensing";
+	AudioStreamControl = 0x184e, "Audio Stream Control";
+	BroadcastAudioScan = 0x184f, "Broadcast Audio Scan";
+	PublishedAudioCapabilities = 0x1850, "Published Audio Capabilities";
+}
+
+impl From<ServiceClass> for BluetoothUuid {
+	fn from(service: ServiceClass) -> Self {
+		service.uuid()
+	}
+}
+
+impl fmt::Display for ServiceClass {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
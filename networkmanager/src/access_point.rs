@@ -3,17 +3,31 @@
 use crate::{
 	interface::{
 		access_point::AccessPointProxy,
-		enums::{ApFlags, ApSecurityFlags},
+		enums::{ApFlags, ApSecurityFlags, WifiMode},
 	},
-	util::clock_boottime_to_time,
+	util::{
+		clock_boottime_to_time, get_all_properties, get_all_properties_at, join_existing,
+		take_property,
+	},
+};
+use std::{collections::HashMap, fmt::Write, ops::Deref};
+use zbus::{
+	Result,
+	names::InterfaceName,
+	zvariant::{OwnedObjectPath, OwnedValue},
 };
-use std::ops::Deref;
-use zbus::Result;
 
 #[derive(Debug)]
 pub struct AccessPoint<'a>(AccessPointProxy<'a>);
 
 impl<'a> AccessPoint<'a> {
+	/// Fetch all properties of the access point in one round-trip.
+	pub async fn info(&self) -> Result<AccessPointInfo> {
+		get_all_properties(self.0.inner())
+			.await
+			.map(AccessPointInfo::new)
+	}
+
 	pub async fn last_seen(&self) -> Result<Option<jiff::Timestamp>> {
 		Ok(clock_boottime_to_time(self.0.last_seen().await?))
 	}
@@ -50,3 +64,193 @@ impl<'a> From<AccessPointProxy<'a>> for AccessPoint<'a> {
 		AccessPoint(access_point)
 	}
 }
+
+/// A decoded view of all properties of an access point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessPointInfo {
+	/// The SSID for display, see [`decode_ssid`].
+	pub ssid: String,
+	pub ssid_bytes: Vec<u8>,
+	pub hw_address: String,
+	/// The radio frequency in MHz.
+	pub frequency: u32,
+	pub band: Option<WifiBand>,
+	pub channel: Option<u32>,
+	/// The signal quality in percent.
+	pub strength: u8,
+	/// The signal quality as a level from 0 to 4.
+	pub bars: u8,
+	/// The maximum bitrate in kbit/s.
+	pub max_bitrate: u32,
+	pub mode: WifiMode,
+	pub security: SecurityType,
+	pub flags: ApFlags,
+	pub wpa_flags: ApSecurityFlags,
+	pub rsn_flags: ApSecurityFlags,
+	pub last_seen: Option<jiff::Timestamp>,
+}
+
+impl AccessPointInfo {
+	/// Decodes the result of `GetAll` on an access point.
+	pub fn new(mut properties: HashMap<String, OwnedValue>) -> Self {
+		let ssid: Vec<u8> = take_property(&mut properties, "Ssid").unwrap_or_default();
+		let frequency = take_property(&mut properties, "Frequency").unwrap_or_default();
+		let strength = take_property(&mut properties, "Strength").unwrap_or_default();
+		let flags = take_property(&mut properties, "Flags")
+			.map(ApFlags::from_bits_truncate)
+			.unwrap_or_else(ApFlags::empty);
+		let wpa_flags = take_property(&mut properties, "WpaFlags")
+			.map(ApSecurityFlags::from_bits_truncate)
+			.unwrap_or_else(ApSecurityFlags::empty);
+		let rsn_flags = take_property(&mut properties, "RsnFlags")
+			.map(ApSecurityFlags::from_bits_truncate)
+			.unwrap_or_else(ApSecurityFlags::empty);
+
+		AccessPointInfo {
+			ssid: decode_ssid(&ssid),
+			ssid_bytes: ssid,
+			hw_address: take_property(&mut properties, "HwAddress").unwrap_or_default(),
+			frequency,
+			band: WifiBand::from_frequency(frequency),
+			channel: channel_from_frequency(frequency),
+			strength,
+			bars: signal_bars(strength),
+			max_bitrate: take_property(&mut properties, "MaxBitrate").unwrap_or_default(),
+			mode: take_property::<u32>(&mut properties, "Mode")
+				.map(WifiMode::from)
+				.unwrap_or_default(),
+			security: SecurityType::new(flags, wpa_flags, rsn_flags),
+			flags,
+			wpa_flags,
+			rsn_flags,
+			last_seen: take_property(&mut properties, "LastSeen").and_then(clock_boottime_to_time),
+		}
+	}
+}
+
+/// An access point's object path with its decoded properties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessPointSnapshot {
+	pub path: OwnedObjectPath,
+	pub info: AccessPointInfo,
+}
+
+impl AccessPointSnapshot {
+	/// Fetch the properties of the access points at `paths` concurrently, one
+	/// `GetAll` call each and without building a proxy per access point. Access
+	/// points that disappear in the meantime, as they do during scans, are left
+	/// out.
+	pub async fn fetch_all(
+		connection: &zbus::Connection,
+		paths: Vec<OwnedObjectPath>,
+	) -> Result<Vec<AccessPointSnapshot>> {
+		join_existing(paths.into_iter().map(|path| async move {
+			let interface = InterfaceName::from_static_str_unchecked(
+				"org.freedesktop.NetworkManager.AccessPoint",
+			);
+			let properties = get_all_properties_at(connection, &path, interface).await?;
+			Ok(AccessPointSnapshot {
+				path,
+				info: AccessPointInfo::new(properties),
+			})
+		}))
+		.await
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WifiBand {
+	Ghz2_4,
+	Ghz5,
+	Ghz6,
+}
+
+impl WifiBand {
+	pub fn from_frequency(frequency: u32) -> Option<WifiBand> {
+		match frequency {
+			2401..=2495 => Some(WifiBand::Ghz2_4),
+			4900..=5899 => Some(WifiBand::Ghz5),
+			5925..=7125 => Some(WifiBand::Ghz6),
+			_ => None,
+		}
+	}
+}
+
+/// The channel number of a frequency in MHz.
+pub fn channel_from_frequency(frequency: u32) -> Option<u32> {
+	match frequency {
+		2484 => Some(14),
+		2412..=2472 => Some((frequency - 2407) / 5),
+		4915..=4980 => Some((frequency - 4000) / 5),
+		5035..=5895 => Some((frequency - 5000) / 5),
+		5935 => Some(2),
+		5955..=7115 => Some((frequency - 5950) / 5),
+		_ => None,
+	}
+}
+
+/// Maps a signal strength in percent to a level from 0 to 4, with the same
+/// thresholds as `nmcli`.
+pub fn signal_bars(strength: u8) -> u8 {
+	match strength {
+		81.. => 4,
+		56..=80 => 3,
+		31..=55 => 2,
+		6..=30 => 1,
+		_ => 0,
+	}
+}
+
+/// Decodes an SSID for display. SSIDs are arbitrary bytes, so invalid UTF-8 is
+/// replaced lossily, and SSIDs that would not display meaningfully (control
+/// characters or nothing but replacement characters) are shown as hex instead.
+pub fn decode_ssid(ssid: &[u8]) -> String {
+	let decoded = String::from_utf8_lossy(ssid);
+	let printable = decoded.chars().any(|c| c != char::REPLACEMENT_CHARACTER)
+		&& !decoded.chars().any(char::is_control);
+	if printable || ssid.is_empty() {
+		return decoded.into_owned();
+	}
+	ssid.iter()
+		.fold(String::with_capacity(ssid.len() * 2), |mut hex, byte| {
+			let _ = write!(hex, "{byte:02x}");
+			hex
+		})
+}
+
+/// The strongest security scheme an access point offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecurityType {
+	Open,
+	Wep,
+	Wpa,
+	Wpa2,
+	Wpa3Sae,
+	Owe,
+	Enterprise,
+}
+
+impl SecurityType {
+	pub fn new(flags: ApFlags, wpa_flags: ApSecurityFlags, rsn_flags: ApSecurityFlags) -> Self {
+		let key_mgmt = wpa_flags | rsn_flags;
+		if key_mgmt.intersects(
+			ApSecurityFlags::KEY_MGMT_802_1X | ApSecurityFlags::KEY_MGMT_EAP_SUITE_B_192,
+		) {
+			SecurityType::Enterprise
+		} else if rsn_flags.contains(ApSecurityFlags::KEY_MGMT_SAE) {
+			SecurityType::Wpa3Sae
+		} else if rsn_flags.contains(ApSecurityFlags::KEY_MGMTPSK) {
+			SecurityType::Wpa2
+		} else if wpa_flags.contains(ApSecurityFlags::KEY_MGMTPSK) {
+			SecurityType::Wpa
+		} else if rsn_flags
+			.intersects(ApSecurityFlags::KEY_MGMT_OWE | ApSecurityFlags::KEY_MGMT_OWE_TM)
+		{
+			SecurityType::Owe
+		} else if flags.contains(ApFlags::PRIVACY) {
+			SecurityType::Wep
+		} else {
+			SecurityType::Open
+		}
+	}
+}
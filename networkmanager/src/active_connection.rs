@@ -8,7 +8,10 @@ use crate::{
 		config::{ip4::Ipv4ConfigProxy, ip6::Ipv6ConfigProxy},
 		device::DeviceProxy,
 		enums::{ActivationStateFlags, ActiveConnectionState},
+		settings::connection::ConnectionSettingsProxy,
 	},
+	settings::connection::Connection,
+	util::build_proxies,
 };
 use std::ops::Deref;
 use zbus::Result;
@@ -19,15 +22,17 @@ pub struct ActiveConnection<'a>(ActiveConnectionProxy<'a>);
 impl<'a> ActiveConnection<'a> {
 	pub async fn devices(&self) -> Result<Vec<Device<'a>>> {
 		let devices = self.0.devices().await?;
-		let mut out = Vec::with_capacity(devices.len());
-		for device in devices {
-			let device = DeviceProxy::builder(self.0.inner().connection())
-				.path(device)?
-				.build()
-				.await?;
-			out.push(device.into());
-		}
-		Ok(out)
+		let proxies: Vec<DeviceProxy> = build_proxies(self.0.inner().connection(), devices).await?;
+		Ok(proxies.into_iter().map(Into::into).collect())
+	}
+
+	/// The connection profile this active connection was activated from.
+	pub async fn connection(&self) -> Result<Connection<'a>> {
+		ConnectionSettingsProxy::builder(self.0.inner().connection())
+			.path(self.0.connection_().await?)?
+			.build()
+			.await
+			.map(Connection::from)
 	}
 
 	pub async fn ip4_config(&self) -> Result<Ipv4Config<'a>> {
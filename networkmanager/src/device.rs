@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
 
 pub mod bluetooth;
+pub mod modem;
 pub mod tun;
+pub mod wifi_p2p;
 pub mod wired;
 pub mod wireguard;
 pub mod wireless;
@@ -13,17 +15,30 @@ use crate::{
 		active_connection::ActiveConnectionProxy,
 		config::{ip4::Ipv4ConfigProxy, ip6::Ipv6ConfigProxy},
 		device::{
-			DeviceProxy, bluetooth::BluetoothDeviceProxy, tun::TunDeviceProxy,
-			wired::WiredDeviceProxy, wireguard::WireGuardDeviceProxy,
-			wireless::WirelessDeviceProxy,
+			DeviceProxy, bluetooth::BluetoothDeviceProxy, modem::ModemDeviceProxy,
+			tun::TunDeviceProxy, wifi_p2p::WifiP2PDeviceProxy, wired::WiredDeviceProxy,
+			wireguard::WireGuardDeviceProxy, wireless::WirelessDeviceProxy,
+		},
+		enums::{
+			DeviceCapabilities, DeviceState, DeviceStateReason, DeviceType, InterfaceFlags,
+			NmConnectivityState, NmMetered,
 		},
-		enums::{DeviceCapabilities, DeviceState, DeviceType},
 		settings::connection::ConnectionSettingsProxy,
 	},
-	settings::connection::Connection,
+	lldp::LldpNeighbor,
+	settings::connection::{Connection, Settings},
+	util::{
+		build_proxies, get_all_properties_at, join_existing, owned_settings_to_values,
+		settings_ref, take_property,
+	},
+};
+use futures_util::{Stream, StreamExt};
+use std::{collections::HashMap, net::Ipv4Addr, ops::Deref};
+use zbus::{
+	Result,
+	names::InterfaceName,
+	zvariant::{OwnedObjectPath, OwnedValue, Value},
 };
-use std::{net::Ipv4Addr, ops::Deref};
-use zbus::Result;
 
 #[derive(Debug)]
 pub struct Device<'a>(DeviceProxy<'a>);
@@ -38,17 +53,34 @@ impl<'a> Device<'a> {
 			.into())
 	}
 
+	/// The settings currently applied to the device, along with the version id
+	/// to pass to [`Self::reapply`].
+	pub async fn applied_settings(&self) -> Result<(Settings, u64)> {
+		let (settings, version_id) = self.0.get_applied_connection(0).await?;
+		Ok((Settings::new(settings), version_id))
+	}
+
+	/// Apply changed settings to the device without reactivating its connection.
+	///
+	/// `settings` is merged over the currently applied connection, so settings
+	/// not modelled by [`Settings`] are preserved. The call fails if the applied
+	/// connection changed since `version_id` was obtained, or if a change cannot
+	/// be applied live; see [`Settings::diff`].
+	pub async fn reapply(&self, settings: &Settings, version_id: u64) -> Result<()> {
+		let (applied, _) = self.0.get_applied_connection(0).await?;
+		let mut merged: HashMap<String, HashMap<String, Value<'_>>> =
+			owned_settings_to_values(applied);
+		for (setting, properties) in settings.build() {
+			merged.entry(setting).or_default().extend(properties);
+		}
+		self.0.reapply(settings_ref(&merged), version_id, 0).await
+	}
+
 	pub async fn available_connections(&self) -> Result<Vec<Connection<'a>>> {
 		let available_connections = self.0.available_connections().await?;
-		let mut out = Vec::with_capacity(available_connections.len());
-		for connection in available_connections {
-			let connection = ConnectionSettingsProxy::builder(self.0.inner().connection())
-				.path(connection)?
-				.build()
-				.await?;
-			out.push(connection.into());
-		}
-		Ok(out)
+		let proxies: Vec<ConnectionSettingsProxy> =
+			build_proxies(self.0.inner().connection(), available_connections).await?;
+		Ok(proxies.into_iter().map(Into::into).collect())
 	}
 
 	pub async fn capabilities(&self) -> Result<DeviceCapabilities> {
@@ -85,6 +117,20 @@ impl<'a> Device<'a> {
 					.await?
 					.into(),
 			))),
+			DeviceType::Modem => Ok(Some(SpecificDevice::Modem(
+				ModemDeviceProxy::builder(self.0.inner().connection())
+					.path(self.0.inner().path())?
+					.build()
+					.await?
+					.into(),
+			))),
+			DeviceType::WifiP2P => Ok(Some(SpecificDevice::WifiP2P(
+				WifiP2PDeviceProxy::builder(self.0.inner().connection())
+					.path(self.0.inner().path())?
+					.build()
+					.await?
+					.into(),
+			))),
 			DeviceType::TunTap => Ok(Some(SpecificDevice::TunTap(
 				TunDeviceProxy::builder(self.0.inner().connection())
 					.path(self.0.inner().path())?
@@ -103,6 +149,67 @@ impl<'a> Device<'a> {
 		}
 	}
 
+	/// The permanent hardware address of the device, falling back to the
+	/// current one for device types without a permanent address or when it is
+	/// unknown.
+	pub async fn permanent_hw_address(&self) -> Result<String> {
+		let connection = self.0.inner().connection();
+		let path = self.0.inner().path();
+		let permanent = match self.device_type().await? {
+			DeviceType::Ethernet => WiredDeviceProxy::builder(connection)
+				.path(path)?
+				.build()
+				.await?
+				.perm_hw_address()
+				.await
+				.ok(),
+			DeviceType::Wifi => WirelessDeviceProxy::builder(connection)
+				.path(path)?
+				.build()
+				.await?
+				.perm_hw_address()
+				.await
+				.ok(),
+			_ => None,
+		};
+		match permanent {
+			Some(address) if !address.is_empty() => Ok(address),
+			_ => self.0.hw_address().await,
+		}
+	}
+
+	pub async fn interface_flags(&self) -> Result<InterfaceFlags> {
+		self.0
+			.interface_flags()
+			.await
+			.map(InterfaceFlags::from_bits_truncate)
+	}
+
+	/// Whether the device exists in the kernel, rather than being a placeholder
+	/// for a software device that has not been created yet.
+	pub async fn is_real(&self) -> Result<bool> {
+		self.0.real().await
+	}
+
+	/// Whether NetworkManager manages the device.
+	pub async fn is_managed(&self) -> Result<bool> {
+		self.0.managed().await
+	}
+
+	pub async fn ip4_connectivity(&self) -> Result<NmConnectivityState> {
+		self.0
+			.ip4_connectivity()
+			.await
+			.map(NmConnectivityState::from)
+	}
+
+	pub async fn ip6_connectivity(&self) -> Result<NmConnectivityState> {
+		self.0
+			.ip6_connectivity()
+			.await
+			.map(NmConnectivityState::from)
+	}
+
 	pub async fn ip4_address(&self) -> Result<Ipv4Addr> {
 		self.0.ip4_address().await.map(Ipv4Addr::from)
 	}
@@ -123,9 +230,47 @@ impl<'a> Device<'a> {
 		Ok(Ipv6Config::from(config))
 	}
 
+	/// Neighbors discovered through LLDP. Reception must be enabled on the active
+	/// connection, see [`Connection::set_lldp`].
+	pub async fn lldp_neighbors(&self) -> Result<Vec<LldpNeighbor>> {
+		Ok(self
+			.0
+			.lldp_neighbors()
+			.await?
+			.into_iter()
+			.map(LldpNeighbor::new)
+			.collect())
+	}
+
+	pub async fn metered(&self) -> Result<NmMetered> {
+		self.0.metered().await.map(NmMetered::from)
+	}
+
 	pub async fn state(&self) -> Result<DeviceState> {
 		self.0.state().await.map(DeviceState::from)
 	}
+
+	/// The current state and the reason the device entered it.
+	pub async fn state_reason(&self) -> Result<(DeviceState, DeviceStateReason)> {
+		let (state, reason) = self.0.state_reason().await?;
+		Ok((DeviceState::from(state), DeviceStateReason::from(reason)))
+	}
+
+	/// Watch the state transitions of the device along with their reasons.
+	pub async fn state_changes(&self) -> Result<impl Stream<Item = DeviceStateChange> + use<>> {
+		Ok(self
+			.0
+			.receive_device_state_changed()
+			.await?
+			.filter_map(|signal| async move {
+				let args = signal.args().ok()?;
+				Some(DeviceStateChange {
+					old: DeviceState::from(args.old_state),
+					new: DeviceState::from(args.new_state),
+					reason: DeviceStateReason::from(args.reason),
+				})
+			}))
+	}
 }
 
 impl<'a> Deref for Device<'a> {
@@ -142,10 +287,110 @@ impl<'a> From<DeviceProxy<'a>> for Device<'a> {
 	}
 }
 
+/// A device's object path with its commonly used properties, decoded from a
+/// single `GetAll` call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceSnapshot {
+	pub path: OwnedObjectPath,
+	pub interface: String,
+	pub ip_interface: String,
+	pub driver: String,
+	pub hw_address: String,
+	pub device_type: DeviceType,
+	pub state: DeviceState,
+	pub state_reason: DeviceStateReason,
+	pub capabilities: DeviceCapabilities,
+	pub interface_flags: InterfaceFlags,
+	pub managed: bool,
+	/// Whether the device exists in the kernel, rather than being a placeholder
+	/// for a software device that has not been created yet.
+	pub real: bool,
+	pub autoconnect: bool,
+	pub mtu: u32,
+	pub metered: NmMetered,
+	pub ip4_address: Ipv4Addr,
+	pub ip4_connectivity: NmConnectivityState,
+	pub ip6_connectivity: NmConnectivityState,
+	/// The active connection of the device, if it has one.
+	pub active_connection: Option<OwnedObjectPath>,
+}
+
+impl DeviceSnapshot {
+	/// Decodes the result of `GetAll` on the device at `path`.
+	pub fn new(path: OwnedObjectPath, mut properties: HashMap<String, OwnedValue>) -> Self {
+		let (state, state_reason) =
+			take_property::<(u32, u32)>(&mut properties, "StateReason").unwrap_or_default();
+		DeviceSnapshot {
+			interface: take_property(&mut properties, "Interface").unwrap_or_default(),
+			ip_interface: take_property(&mut properties, "IpInterface").unwrap_or_default(),
+			driver: take_property(&mut properties, "Driver").unwrap_or_default(),
+			hw_address: take_property(&mut properties, "HwAddress").unwrap_or_default(),
+			device_type: take_property::<u32>(&mut properties, "DeviceType")
+				.map(DeviceType::from)
+				.unwrap_or_default(),
+			state: DeviceState::from(state),
+			state_reason: DeviceStateReason::from(state_reason),
+			capabilities: take_property(&mut properties, "Capabilities")
+				.map(DeviceCapabilities::from_bits_truncate)
+				.unwrap_or_else(DeviceCapabilities::empty),
+			interface_flags: take_property(&mut properties, "InterfaceFlags")
+				.map(InterfaceFlags::from_bits_truncate)
+				.unwrap_or_else(InterfaceFlags::empty),
+			managed: take_property(&mut properties, "Managed").unwrap_or_default(),
+			real: take_property(&mut properties, "Real").unwrap_or_default(),
+			autoconnect: take_property(&mut properties, "Autoconnect").unwrap_or_default(),
+			mtu: take_property(&mut properties, "Mtu").unwrap_or_default(),
+			metered: take_property::<u32>(&mut properties, "Metered")
+				.map(NmMetered::from)
+				.unwrap_or_default(),
+			ip4_address: take_property::<u32>(&mut properties, "Ip4Address")
+				.map(Ipv4Addr::from)
+				.unwrap_or(Ipv4Addr::UNSPECIFIED),
+			ip4_connectivity: take_property::<u32>(&mut properties, "Ip4Connectivity")
+				.map(NmConnectivityState::from)
+				.unwrap_or_default(),
+			ip6_connectivity: take_property::<u32>(&mut properties, "Ip6Connectivity")
+				.map(NmConnectivityState::from)
+				.unwrap_or_default(),
+			active_connection: take_property::<OwnedObjectPath>(
+				&mut properties,
+				"ActiveConnection",
+			)
+			.filter(|path| path.as_str() != "/"),
+			path,
+		}
+	}
+
+	/// Fetch the properties of the devices at `paths` concurrently, one `GetAll`
+	/// call each and without building a proxy per device. Devices that
+	/// disappear in the meantime are left out.
+	pub async fn fetch_all(
+		connection: &zbus::Connection,
+		paths: Vec<OwnedObjectPath>,
+	) -> Result<Vec<DeviceSnapshot>> {
+		join_existing(paths.into_iter().map(|path| async move {
+			let interface =
+				InterfaceName::from_static_str_unchecked("org.freedesktop.NetworkManager.Device");
+			let properties = get_all_properties_at(connection, &path, interface).await?;
+			Ok(DeviceSnapshot::new(path, properties))
+		}))
+		.await
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceStateChange {
+	pub old: DeviceState,
+	pub new: DeviceState,
+	pub reason: DeviceStateReason,
+}
+
 pub enum SpecificDevice<'a> {
 	Bluetooth(bluetooth::BluetoothDevice<'a>),
 	Wired(wired::WiredDevice<'a>),
 	Wireless(wireless::WirelessDevice<'a>),
+	Modem(modem::ModemDevice<'a>),
+	WifiP2P(wifi_p2p::WifiP2PDevice<'a>),
 	TunTap(tun::TunDevice<'a>),
 	WireGuard(wireguard::WireGuardDevice<'a>),
 }
@@ -172,6 +417,20 @@ impl<'a> SpecificDevice<'a> {
 		}
 	}
 
+	pub fn into_modem(self) -> Option<modem::ModemDevice<'a>> {
+		match self {
+			SpecificDevice::Modem(device) => Some(device),
+			_ => None,
+		}
+	}
+
+	pub fn into_wifi_p2p(self) -> Option<wifi_p2p::WifiP2PDevice<'a>> {
+		match self {
+			SpecificDevice::WifiP2P(device) => Some(device),
+			_ => None,
+		}
+	}
+
 	pub fn into_tun(self) -> Option<tun::TunDevice<'a>> {
 		match self {
 			SpecificDevice::TunTap(device) => Some(device),
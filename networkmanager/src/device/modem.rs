@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::Device;
+use crate::interface::{
+	device::{DeviceProxy, modem::ModemDeviceProxy},
+	enums::ModemCapabilities,
+};
+use std::ops::Deref;
+use zbus::Result;
+
+#[derive(Debug)]
+pub struct ModemDevice<'a>(ModemDeviceProxy<'a>);
+
+impl<'a> ModemDevice<'a> {
+	pub async fn upcast(&'a self) -> Result<Device<'a>> {
+		DeviceProxy::builder(self.0.inner().connection())
+			.path(self.0.inner().path())?
+			.build()
+			.await
+			.map(Device::from)
+	}
+
+	/// The generic families of access technologies the modem supports.
+	pub async fn modem_capabilities(&self) -> Result<ModemCapabilities> {
+		self.0
+			.modem_capabilities()
+			.await
+			.map(ModemCapabilities::from_bits_truncate)
+	}
+
+	/// The access technologies the modem currently supports without a firmware reload.
+	pub async fn current_capabilities(&self) -> Result<ModemCapabilities> {
+		self.0
+			.current_capabilities()
+			.await
+			.map(ModemCapabilities::from_bits_truncate)
+	}
+}
+
+impl<'a> Deref for ModemDevice<'a> {
+	type Target = ModemDeviceProxy<'a>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<'a> From<ModemDeviceProxy<'a>> for ModemDevice<'a> {
+	fn from(device: ModemDeviceProxy<'a>) -> Self {
+		ModemDevice(device)
+	}
+}
@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::Device;
+use crate::{
+	interface::{
+		device::{DeviceProxy, wifi_p2p::WifiP2PDeviceProxy},
+		wifi_p2p_peer::WifiP2PPeerProxy,
+	},
+	util::build_proxies,
+	wifi_p2p_peer::{WifiP2PPeer, WifiP2PPeerInfo},
+};
+use futures_util::{Stream, StreamExt};
+use std::{collections::HashMap, ops::Deref};
+use zbus::{
+	Result,
+	zvariant::{OwnedObjectPath, Value},
+};
+
+#[derive(Debug)]
+pub struct WifiP2PDevice<'a>(WifiP2PDeviceProxy<'a>);
+
+impl<'a> WifiP2PDevice<'a> {
+	pub async fn upcast(&'a self) -> Result<Device<'a>> {
+		DeviceProxy::builder(self.0.inner().connection())
+			.path(self.0.inner().path())?
+			.build()
+			.await
+			.map(Device::from)
+	}
+
+	/// Start searching for peers. The search stops on its own after `timeout`
+	/// seconds, which NetworkManager limits to 1 to 600 and defaults to 30.
+	pub async fn start_find(&self, timeout: Option<i32>) -> Result<()> {
+		let mut options = HashMap::new();
+		if let Some(timeout) = timeout {
+			options.insert("timeout", Value::from(timeout));
+		}
+		self.0.start_find(options).await
+	}
+
+	pub async fn stop_find(&self) -> Result<()> {
+		self.0.stop_find().await
+	}
+
+	pub async fn peers(&self) -> Result<Vec<WifiP2PPeer<'a>>> {
+		let peers = self.0.peers().await?;
+		let proxies: Vec<WifiP2PPeerProxy> =
+			build_proxies(self.0.inner().connection(), peers).await?;
+		Ok(proxies.into_iter().map(Into::into).collect())
+	}
+
+	/// Watch peers appearing and disappearing. The properties of added peers are
+	/// fetched as they appear; peers which vanish before that are skipped.
+	pub async fn peer_events(&self) -> Result<impl Stream<Item = WifiP2PPeerEvent> + use<>> {
+		let connection = self.0.inner().connection().clone();
+		let added = self
+			.0
+			.receive_peer_added()
+			.await?
+			.filter_map(move |signal| {
+				let connection = connection.clone();
+				async move {
+					let path = OwnedObjectPath::from(signal.args().ok()?.peer);
+					let info = WifiP2PPeerInfo::fetch(&connection, &path).await.ok()?;
+					Some(WifiP2PPeerEvent::Added { path, info })
+				}
+			});
+		let removed = self
+			.0
+			.receive_peer_removed()
+			.await?
+			.filter_map(|signal| async move {
+				let path = OwnedObjectPath::from(signal.args().ok()?.peer);
+				Some(WifiP2PPeerEvent::Removed(path))
+			});
+		Ok(futures_util::stream::select(added, removed))
+	}
+}
+
+impl<'a> Deref for WifiP2PDevice<'a> {
+	type Target = WifiP2PDeviceProxy<'a>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<'a> From<WifiP2PDeviceProxy<'a>> for WifiP2PDevice<'a> {
+	fn from(device: WifiP2PDeviceProxy<'a>) -> Self {
+		WifiP2PDevice(device)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WifiP2PPeerEvent {
+	Added {
+		path: OwnedObjectPath,
+		info: WifiP2PPeerInfo,
+	},
+	Removed(OwnedObjectPath),
+}
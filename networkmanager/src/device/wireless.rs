@@ -2,12 +2,13 @@
 
 use super::Device;
 use crate::{
-	access_point::AccessPoint,
+	access_point::{AccessPoint, AccessPointSnapshot},
 	interface::{
 		access_point::AccessPointProxy,
 		device::{DeviceProxy, wireless::WirelessDeviceProxy},
 		enums::{WifiCapabilities, WifiMode},
 	},
+	util::build_proxies,
 };
 use std::ops::Deref;
 use zbus::Result;
@@ -18,41 +19,30 @@ pub struct WirelessDevice<'a>(WirelessDeviceProxy<'a>);
 impl<'a> WirelessDevice<'a> {
 	pub async fn get_access_points(&self) -> Result<Vec<AccessPoint<'a>>> {
 		let access_points = self.0.get_access_points().await?;
-		let mut out = Vec::with_capacity(access_points.len());
-		for access_point in access_points {
-			let access_point = AccessPointProxy::builder(self.0.inner().connection())
-				.path(access_point)?
-				.build()
-				.await?;
-			out.push(access_point.into());
-		}
-		Ok(out)
+		let proxies: Vec<AccessPointProxy> =
+			build_proxies(self.0.inner().connection(), access_points).await?;
+		Ok(proxies.into_iter().map(Into::into).collect())
 	}
 
 	pub async fn get_all_access_points(&self) -> Result<Vec<AccessPoint<'a>>> {
 		let access_points = self.0.get_all_access_points().await?;
-		let mut out = Vec::with_capacity(access_points.len());
-		for access_point in access_points {
-			let access_point = AccessPointProxy::builder(self.0.inner().connection())
-				.path(access_point)?
-				.build()
-				.await?;
-			out.push(access_point.into());
-		}
-		Ok(out)
+		let proxies: Vec<AccessPointProxy> =
+			build_proxies(self.0.inner().connection(), access_points).await?;
+		Ok(proxies.into_iter().map(Into::into).collect())
 	}
 
 	pub async fn access_points(&self) -> Result<Vec<AccessPoint<'a>>> {
 		let access_points = self.0.access_points().await?;
-		let mut out = Vec::with_capacity(access_points.len());
-		for access_point in access_points {
-			let access_point = AccessPointProxy::builder(self.0.inner().connection())
-				.path(access_point)?
-				.build()
-				.await?;
-			out.push(access_point.into());
-		}
-		Ok(out)
+		let proxies: Vec<AccessPointProxy> =
+			build_proxies(self.0.inner().connection(), access_points).await?;
+		Ok(proxies.into_iter().map(Into::into).collect())
+	}
+
+	/// Fetch the properties of all visible access points concurrently, one
+	/// `GetAll` call each, without building an [`AccessPoint`] per path.
+	pub async fn access_point_snapshots(&self) -> Result<Vec<AccessPointSnapshot>> {
+		let access_points = self.0.access_points().await?;
+		AccessPointSnapshot::fetch_all(self.0.inner().connection(), access_points).await
 	}
 
 	pub async fn active_access_point(&self) -> Result<AccessPoint<'a>> {
@@ -0,0 +1,303 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::util::{get_all_properties_at, take_property};
+use std::{
+	collections::HashMap,
+	net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+use zbus::{
+	Result,
+	names::InterfaceName,
+	zvariant::{OwnedObjectPath, OwnedValue},
+};
+
+/// The effective resolver configuration, gathered from every active connection
+/// and the global DNS configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DnsSummary {
+	/// Per-connection DNS configuration, ordered from highest to lowest priority.
+	pub connections: Vec<ConnectionDns>,
+	/// The global DNS configuration, which takes precedence over connections.
+	pub global: Option<GlobalDnsConfiguration>,
+}
+
+impl DnsSummary {
+	/// The name servers in use, in the order they are queried. Servers set for
+	/// all domains in the global configuration override those of connections, and
+	/// a connection with a negative priority excludes those with a lower one.
+	pub fn nameservers(&self) -> Vec<IpAddr> {
+		if let Some(servers) = self
+			.global
+			.as_ref()
+			.and_then(|global| global.domains.get("*"))
+			.map(|domain| &domain.servers)
+			.filter(|servers| !servers.is_empty())
+		{
+			return servers.clone();
+		}
+
+		let mut out = Vec::new();
+		for dns in self.effective_configs() {
+			for server in &dns.nameservers {
+				if !out.contains(server) {
+					out.push(*server);
+				}
+			}
+		}
+		out
+	}
+
+	/// The search domains in use, global ones first.
+	pub fn search_domains(&self) -> Vec<String> {
+		let mut out: Vec<String> = Vec::new();
+		let global = self.global.iter().flat_map(|global| &global.searches);
+		let connections = self
+			.effective_configs()
+			.flat_map(|dns| dns.searches.iter().chain(&dns.domains));
+		for domain in global.chain(connections) {
+			if !out.contains(domain) {
+				out.push(domain.clone());
+			}
+		}
+		out
+	}
+
+	/// The connection carrying the default IPv4 or IPv6 route, if any.
+	pub fn default_connection(&self) -> Option<&ConnectionDns> {
+		self.connections
+			.iter()
+			.find(|connection| connection.default || connection.default6)
+	}
+
+	/// The IPv4 and IPv6 configurations not excluded by a negative priority,
+	/// ordered by priority.
+	fn effective_configs(&self) -> impl Iterator<Item = &DnsConfig> {
+		let mut configs: Vec<&DnsConfig> = self
+			.connections
+			.iter()
+			.flat_map(|connection| connection.ipv4.iter().chain(&connection.ipv6))
+			.collect();
+		configs.sort_by_key(|dns| dns.priority);
+		let cutoff = configs
+			.iter()
+			.map(|dns| dns.priority)
+			.filter(|priority| *priority < 0)
+			.min();
+		configs
+			.into_iter()
+			.filter(move |dns| cutoff.is_none_or(|cutoff| dns.priority <= cutoff))
+	}
+
+	pub(crate) async fn fetch(
+		connection: &zbus::Connection,
+		active_connections: Vec<OwnedObjectPath>,
+		global: HashMap<String, OwnedValue>,
+	) -> Result<Self> {
+		// A connection that deactivates while it is fetched is left out.
+		let mut connections: Vec<ConnectionDns> = futures_util::future::join_all(
+			active_connections
+				.iter()
+				.map(|path| ConnectionDns::fetch(connection, path)),
+		)
+		.await
+		.into_iter()
+		.filter_map(Result::ok)
+		.collect();
+		connections.sort_by_key(|connection| {
+			let priority = connection
+				.ipv4
+				.iter()
+				.chain(&connection.ipv6)
+				.map(|dns| dns.priority)
+				.min()
+				.unwrap_or(i32::MAX);
+			(priority, !(connection.default || connection.default6))
+		});
+		Ok(DnsSummary {
+			connections,
+			global: GlobalDnsConfiguration::new(global),
+		})
+	}
+}
+
+/// The DNS configuration of an active connection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionDns {
+	pub path: OwnedObjectPath,
+	pub id: String,
+	pub uuid: String,
+	pub vpn: bool,
+	/// Whether the connection carries the default IPv4 route.
+	pub default: bool,
+	/// Whether the connection carries the default IPv6 route.
+	pub default6: bool,
+	pub ipv4: Option<DnsConfig>,
+	pub ipv6: Option<DnsConfig>,
+}
+
+impl ConnectionDns {
+	async fn fetch(connection: &zbus::Connection, path: &OwnedObjectPath) -> Result<Self> {
+		let mut properties = get_all_properties_at(
+			connection,
+			path,
+			InterfaceName::from_static_str_unchecked(
+				"org.freedesktop.NetworkManager.Connection.Active",
+			),
+		)
+		.await?;
+		let vpn = take_property(&mut properties, "Vpn").unwrap_or_default();
+		let ip4_config = take_property::<OwnedObjectPath>(&mut properties, "Ip4Config")
+			.filter(|path| path.as_str() != "/");
+		let ip6_config = take_property::<OwnedObjectPath>(&mut properties, "Ip6Config")
+			.filter(|path| path.as_str() != "/");
+
+		let (ipv4, ipv6) = futures_util::future::try_join(
+			async {
+				match ip4_config {
+					Some(path) => DnsConfig::fetch_ipv4(connection, &path, vpn)
+						.await
+						.map(Some),
+					None => Ok(None),
+				}
+			},
+			async {
+				match ip6_config {
+					Some(path) => DnsConfig::fetch_ipv6(connection, &path, vpn)
+						.await
+						.map(Some),
+					None => Ok(None),
+				}
+			},
+		)
+		.await?;
+
+		Ok(ConnectionDns {
+			path: path.clone(),
+			id: take_property(&mut properties, "Id").unwrap_or_default(),
+			uuid: take_property(&mut properties, "Uuid").unwrap_or_default(),
+			vpn,
+			default: take_property(&mut properties, "Default").unwrap_or_default(),
+			default6: take_property(&mut properties, "Default6").unwrap_or_default(),
+			ipv4,
+			ipv6,
+		})
+	}
+}
+
+/// The DNS settings of one IP configuration of a connection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DnsConfig {
+	pub nameservers: Vec<IpAddr>,
+	/// Domains for which the name servers are used.
+	pub domains: Vec<String>,
+	/// Domains appended to single-label host names.
+	pub searches: Vec<String>,
+	pub options: Vec<String>,
+	/// The effective priority, lower values being preferred. An unset priority
+	/// is resolved to NetworkManager's default of 50 for VPNs and 100 otherwise.
+	pub priority: i32,
+}
+
+impl DnsConfig {
+	async fn fetch_ipv4(
+		connection: &zbus::Connection,
+		path: &OwnedObjectPath,
+		vpn: bool,
+	) -> Result<Self> {
+		let interface =
+			InterfaceName::from_static_str_unchecked("org.freedesktop.NetworkManager.IP4Config");
+		let mut properties = get_all_properties_at(connection, path, interface).await?;
+		let nameservers =
+			take_property::<Vec<HashMap<String, OwnedValue>>>(&mut properties, "NameserverData")
+				.unwrap_or_default()
+				.into_iter()
+				.filter_map(|mut data| {
+					take_property::<String>(&mut data, "address")?
+						.parse::<Ipv4Addr>()
+						.ok()
+						.map(IpAddr::from)
+				})
+				.collect();
+		Ok(Self::new(nameservers, properties, vpn))
+	}
+
+	async fn fetch_ipv6(
+		connection: &zbus::Connection,
+		path: &OwnedObjectPath,
+		vpn: bool,
+	) -> Result<Self> {
+		let interface =
+			InterfaceName::from_static_str_unchecked("org.freedesktop.NetworkManager.IP6Config");
+		let mut properties = get_all_properties_at(connection, path, interface).await?;
+		let nameservers = take_property::<Vec<Vec<u8>>>(&mut properties, "Nameservers")
+			.unwrap_or_default()
+			.into_iter()
+			.filter_map(|address| <[u8; 16]>::try_from(address).ok())
+			.map(|address| IpAddr::from(Ipv6Addr::from(address)))
+			.collect();
+		Ok(Self::new(nameservers, properties, vpn))
+	}
+
+	fn new(
+		nameservers: Vec<IpAddr>,
+		mut properties: HashMap<String, OwnedValue>,
+		vpn: bool,
+	) -> Self {
+		let priority = match take_property(&mut properties, "DnsPriority").unwrap_or_default() {
+			0 if vpn => 50,
+			0 => 100,
+			priority => priority,
+		};
+		DnsConfig {
+			nameservers,
+			domains: take_property(&mut properties, "Domains").unwrap_or_default(),
+			searches: take_property(&mut properties, "Searches").unwrap_or_default(),
+			options: take_property(&mut properties, "DnsOptions").unwrap_or_default(),
+			priority,
+		}
+	}
+}
+
+/// The `GlobalDnsConfiguration` of NetworkManager, set in `NetworkManager.conf`
+/// or over D-Bus.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GlobalDnsConfiguration {
+	pub searches: Vec<String>,
+	pub options: Vec<String>,
+	/// Name servers per domain, where `*` applies to all domains.
+	pub domains: HashMap<String, GlobalDnsDomain>,
+}
+
+impl GlobalDnsConfiguration {
+	/// Decodes the `GlobalDnsConfiguration` property, returning `None` if it is empty.
+	pub fn new(mut properties: HashMap<String, OwnedValue>) -> Option<Self> {
+		if properties.is_empty() {
+			return None;
+		}
+		let domains = take_property::<HashMap<String, OwnedValue>>(&mut properties, "domains")
+			.unwrap_or_default()
+			.into_iter()
+			.filter_map(|(name, domain)| {
+				let mut domain = HashMap::<String, OwnedValue>::try_from(domain).ok()?;
+				let servers = take_property::<Vec<String>>(&mut domain, "servers")
+					.unwrap_or_default()
+					.iter()
+					.filter_map(|server| server.parse().ok())
+					.collect();
+				let options = take_property(&mut domain, "options").unwrap_or_default();
+				Some((name, GlobalDnsDomain { servers, options }))
+			})
+			.collect();
+		Some(GlobalDnsConfiguration {
+			searches: take_property(&mut properties, "searches").unwrap_or_default(),
+			options: take_property(&mut properties, "options").unwrap_or_default(),
+			domains,
+		})
+	}
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GlobalDnsDomain {
+	pub servers: Vec<IpAddr>,
+	pub options: Vec<String>,
+}
@@ -21,7 +21,9 @@
 //! …consequently `zbus-xmlgen` did not generate code for the above interfaces.
 
 pub mod bluetooth;
+pub mod modem;
 pub mod tun;
+pub mod wifi_p2p;
 pub mod wired;
 pub mod wireguard;
 pub mod wireless;
@@ -76,6 +78,11 @@ pub trait Device {
 		flags: u32,
 	) -> zbus::Result<()>;
 
+	/// StateChanged signal, renamed as it clashes with the change stream of the State property
+	#[zbus(signal, name = "StateChanged")]
+	fn device_state_changed(&self, new_state: u32, old_state: u32, reason: u32)
+	-> zbus::Result<()>;
+
 	/// ActiveConnection property
 	#[zbus(property)]
 	fn active_connection(&self) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MPL-2.0
+//! # DBus interface proxy for: `org.freedesktop.NetworkManager.Device.WifiP2P`
+//!
+//! This code was generated by `zbus-xmlgen` `2.0.0` from DBus introspection data.
+//! Source: `org.freedesktop.NetworkManager.Device.WifiP2P.xml`.
+//!
+//! You may prefer to adapt it, instead of using it verbatim.
+//!
+//! More information can be found in the
+//! [Writing a client proxy](https://dbus.pages.freedesktop.org/zbus/client.html)
+//! section of the zbus documentation.
+//!
+
+use zbus::proxy;
+
+#[proxy(
+	interface = "org.freedesktop.NetworkManager.Device.WifiP2P",
+	default_service = "org.freedesktop.NetworkManager"
+)]
+pub trait WifiP2PDevice {
+	/// StartFind method
+	fn start_find(
+		&self,
+		options: std::collections::HashMap<&str, zbus::zvariant::Value<'_>>,
+	) -> zbus::Result<()>;
+
+	/// StopFind method
+	fn stop_find(&self) -> zbus::Result<()>;
+
+	/// PeerAdded signal
+	#[zbus(signal)]
+	fn peer_added(&self, peer: zbus::zvariant::ObjectPath<'_>) -> zbus::Result<()>;
+
+	/// PeerRemoved signal
+	#[zbus(signal)]
+	fn peer_removed(&self, peer: zbus::zvariant::ObjectPath<'_>) -> zbus::Result<()>;
+
+	/// HwAddress property
+	#[zbus(property)]
+	fn hw_address(&self) -> zbus::Result<String>;
+
+	/// Peers property
+	#[zbus(property)]
+	fn peers(&self) -> zbus::Result<Vec<zbus::zvariant::OwnedObjectPath>>;
+}
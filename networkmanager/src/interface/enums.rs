@@ -51,13 +51,45 @@ impl From<u32> for NmConnectivityState {
 	}
 }
 
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NmMetered {
+	Yes,
+	No,
+	GuessYes,
+	GuessNo,
+	#[default]
+	Unknown,
+}
+
+impl NmMetered {
+	/// Whether traffic should be treated as metered, either because it was
+	/// configured so or because NetworkManager guessed it.
+	pub fn is_metered(self) -> bool {
+		matches!(self, NmMetered::Yes | NmMetered::GuessYes)
+	}
+}
+
+impl From<u32> for NmMetered {
+	fn from(metered: u32) -> NmMetered {
+		match metered {
+			1 => NmMetered::Yes,
+			2 => NmMetered::No,
+			3 => NmMetered::GuessYes,
+			4 => NmMetered::GuessNo,
+			_ => NmMetered::Unknown,
+		}
+	}
+}
+
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeviceType {
 	Ethernet,
 	Wifi,
 	Bluetooth,
+	Modem,
 	TunTap,
 	WireGuard,
+	WifiP2P,
 	Generic,
 	Other,
 	#[default]
@@ -70,9 +102,11 @@ impl From<u32> for DeviceType {
 			1 => DeviceType::Ethernet,
 			2 => DeviceType::Wifi,
 			5 => DeviceType::Bluetooth,
+			8 => DeviceType::Modem,
 			14 => DeviceType::Generic,
 			16 => DeviceType::TunTap,
 			29 => DeviceType::WireGuard,
+			30 => DeviceType::WifiP2P,
 			3..=32 => DeviceType::Other,
 			_ => DeviceType::Unknown,
 		}
@@ -117,6 +151,179 @@ impl From<u32> for DeviceState {
 	}
 }
 
+/// Why a device changed its state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceStateReason {
+	None,
+	NowManaged,
+	NowUnmanaged,
+	ConfigFailed,
+	IpConfigUnavailable,
+	IpConfigExpired,
+	NoSecrets,
+	SupplicantDisconnect,
+	SupplicantConfigFailed,
+	SupplicantFailed,
+	SupplicantTimeout,
+	PppStartFailed,
+	PppDisconnect,
+	PppFailed,
+	DhcpStartFailed,
+	DhcpError,
+	DhcpFailed,
+	SharedStartFailed,
+	SharedFailed,
+	AutoIpStartFailed,
+	AutoIpError,
+	AutoIpFailed,
+	ModemBusy,
+	ModemNoDialTone,
+	ModemNoCarrier,
+	ModemDialTimeout,
+	ModemDialFailed,
+	ModemInitFailed,
+	GsmApnFailed,
+	GsmRegistrationNotSearching,
+	GsmRegistrationDenied,
+	GsmRegistrationTimeout,
+	GsmRegistrationFailed,
+	GsmPinCheckFailed,
+	FirmwareMissing,
+	Removed,
+	Sleeping,
+	ConnectionRemoved,
+	UserRequested,
+	Carrier,
+	ConnectionAssumed,
+	SupplicantAvailable,
+	ModemNotFound,
+	BtFailed,
+	GsmSimNotInserted,
+	GsmSimPinRequired,
+	GsmSimPukRequired,
+	GsmSimWrong,
+	InfinibandMode,
+	DependencyFailed,
+	Br2684Failed,
+	ModemManagerUnavailable,
+	SsidNotFound,
+	SecondaryConnectionFailed,
+	DcbFcoeFailed,
+	TeamdControlFailed,
+	ModemFailed,
+	ModemAvailable,
+	SimPinIncorrect,
+	NewActivation,
+	ParentChanged,
+	ParentManagedChanged,
+	OvsdbFailed,
+	IpAddressDuplicate,
+	IpMethodUnsupported,
+	SriovConfigurationFailed,
+	PeerNotFound,
+	DeviceHandlerFailed,
+	UnmanagedByDefault,
+	UnmanagedExternalDown,
+	UnmanagedLinkNotInit,
+	UnmanagedQuitting,
+	UnmanagedSleeping,
+	UnmanagedUserConf,
+	UnmanagedUserExplicit,
+	UnmanagedUserSettings,
+	UnmanagedUserUdev,
+	NetworkingOff,
+	ModemNoOperatorCode,
+	/// A reason added to NetworkManager after this crate, with its raw value.
+	Unknown(u32),
+}
+
+impl From<u32> for DeviceStateReason {
+	fn from(reason: u32) -> Self {
+		match reason {
+			0 => DeviceStateReason::None,
+			2 => DeviceStateReason::NowManaged,
+			3 => DeviceStateReason::NowUnmanaged,
+			4 => DeviceStateReason::ConfigFailed,
+			5 => DeviceStateReason::IpConfigUnavailable,
+			6 => DeviceStateReason::IpConfigExpired,
+			7 => DeviceStateReason::NoSecrets,
+			8 => DeviceStateReason::SupplicantDisconnect,
+			9 => DeviceStateReason::SupplicantConfigFailed,
+			10 => DeviceStateReason::SupplicantFailed,
+			11 => DeviceStateReason::SupplicantTimeout,
+			12 => DeviceStateReason::PppStartFailed,
+			13 => DeviceStateReason::PppDisconnect,
+			14 => DeviceStateReason::PppFailed,
+			15 => DeviceStateReason::DhcpStartFailed,
+			16 => DeviceStateReason::DhcpError,
+			17 => DeviceStateReason::DhcpFailed,
+			18 => DeviceStateReason::SharedStartFailed,
+			19 => DeviceStateReason::SharedFailed,
+			20 => DeviceStateReason::AutoIpStartFailed,
+			21 => DeviceStateReason::AutoIpError,
+			22 => DeviceStateReason::AutoIpFailed,
+			23 => DeviceStateReason::ModemBusy,
+			24 => DeviceStateReason::ModemNoDialTone,
+			25 => DeviceStateReason::ModemNoCarrier,
+			26 => DeviceStateReason::ModemDialTimeout,
+			27 => DeviceStateReason::ModemDialFailed,
+			28 => DeviceStateReason::ModemInitFailed,
+			29 => DeviceStateReason::GsmApnFailed,
+			30 => DeviceStateReason::GsmRegistrationNotSearching,
+			31 => DeviceStateReason::GsmRegistrationDenied,
+			32 => DeviceStateReason::GsmRegistrationTimeout,
+			33 => DeviceStateReason::GsmRegistrationFailed,
+			34 => DeviceStateReason::GsmPinCheckFailed,
+			35 => DeviceStateReason::FirmwareMissing,
+			36 => DeviceStateReason::Removed,
+			37 => DeviceStateReason::Sleeping,
+			38 => DeviceStateReason::ConnectionRemoved,
+			39 => DeviceStateReason::UserRequested,
+			40 => DeviceStateReason::Carrier,
+			41 => DeviceStateReason::ConnectionAssumed,
+			42 => DeviceStateReason::SupplicantAvailable,
+			43 => DeviceStateReason::ModemNotFound,
+			44 => DeviceStateReason::BtFailed,
+			45 => DeviceStateReason::GsmSimNotInserted,
+			46 => DeviceStateReason::GsmSimPinRequired,
+			47 => DeviceStateReason::GsmSimPukRequired,
+			48 => DeviceStateReason::GsmSimWrong,
+			49 => DeviceStateReason::InfinibandMode,
+			50 => DeviceStateReason::DependencyFailed,
+			51 => DeviceStateReason::Br2684Failed,
+			52 => DeviceStateReason::ModemManagerUnavailable,
+			53 => DeviceStateReason::SsidNotFound,
+			54 => DeviceStateReason::SecondaryConnectionFailed,
+			55 => DeviceStateReason::DcbFcoeFailed,
+			56 => DeviceStateReason::TeamdControlFailed,
+			57 => DeviceStateReason::ModemFailed,
+			58 => DeviceStateReason::ModemAvailable,
+			59 => DeviceStateReason::SimPinIncorrect,
+			60 => DeviceStateReason::NewActivation,
+			61 => DeviceStateReason::ParentChanged,
+			62 => DeviceStateReason::ParentManagedChanged,
+			63 => DeviceStateReason::OvsdbFailed,
+			64 => DeviceStateReason::IpAddressDuplicate,
+			65 => DeviceStateReason::IpMethodUnsupported,
+			66 => DeviceStateReason::SriovConfigurationFailed,
+			67 => DeviceStateReason::PeerNotFound,
+			68 => DeviceStateReason::DeviceHandlerFailed,
+			69 => DeviceStateReason::UnmanagedByDefault,
+			70 => DeviceStateReason::UnmanagedExternalDown,
+			71 => DeviceStateReason::UnmanagedLinkNotInit,
+			72 => DeviceStateReason::UnmanagedQuitting,
+			73 => DeviceStateReason::UnmanagedSleeping,
+			74 => DeviceStateReason::UnmanagedUserConf,
+			75 => DeviceStateReason::UnmanagedUserExplicit,
+			76 => DeviceStateReason::UnmanagedUserSettings,
+			77 => DeviceStateReason::UnmanagedUserUdev,
+			78 => DeviceStateReason::NetworkingOff,
+			79 => DeviceStateReason::ModemNoOperatorCode,
+			reason => DeviceStateReason::Unknown(reason),
+		}
+	}
+}
+
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ActiveConnectionState {
 	#[default]
@@ -162,6 +369,7 @@ impl From<u32> for WifiMode {
 }
 
 bitflags! {
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 	pub struct DeviceCapabilities: u32 {
 		const SUPPORTED = 0x00000001;
 		const CARRIER_DETECT = 0x00000002;
@@ -170,6 +378,17 @@ bitflags! {
 	}
 }
 
+bitflags! {
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub struct InterfaceFlags: u32 {
+		const UP = 0x1;
+		const LOWER_UP = 0x2;
+		const PROMISC = 0x4;
+		const CARRIER = 0x10000;
+		const LLDP_CLIENT_ENABLED = 0x20000;
+	}
+}
+
 bitflags! {
 	pub struct WifiCapabilities: u32 {
 		const CIPHER_WEP40 = 0x00000001;
@@ -188,6 +407,16 @@ bitflags! {
 	}
 }
 
+bitflags! {
+	pub struct ModemCapabilities: u32 {
+		const POTS = 0x00000001;
+		const CDMA_EVDO = 0x00000002;
+		const GSM_UMTS = 0x00000004;
+		const LTE = 0x00000008;
+		const NR_5G = 0x00000040;
+	}
+}
+
 bitflags! {
 	pub struct ActivationStateFlags: u32 {
 		const IS_MASTER = 0x1;
@@ -202,6 +431,7 @@ bitflags! {
 }
 
 bitflags! {
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 	pub struct ApFlags: u32 {
 		const PRIVACY = 0x1;
 		const WPS = 0x2;
@@ -211,6 +441,7 @@ bitflags! {
 }
 
 bitflags! {
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 	pub struct ApSecurityFlags: u32 {
 		const WEP40 = 0x1;
 		const WEP104 = 0x2;
@@ -228,3 +459,135 @@ bitflags! {
 		const KEY_MGMT_EAP_SUITE_B_192 = 0x2000;
 	}
 }
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+	Off,
+	Err,
+	Warn,
+	Info,
+	Debug,
+	Trace,
+	#[default]
+	Unknown,
+}
+
+impl LogLevel {
+	/// The level name understood by `SetLogging`. `Unknown` maps to `KEEP`,
+	/// which leaves the current level unchanged.
+	pub fn as_str(self) -> &'static str {
+		match self {
+			LogLevel::Off => "OFF",
+			LogLevel::Err => "ERR",
+			LogLevel::Warn => "WARN",
+			LogLevel::Info => "INFO",
+			LogLevel::Debug => "DEBUG",
+			LogLevel::Trace => "TRACE",
+			LogLevel::Unknown => "KEEP",
+		}
+	}
+}
+
+impl From<&str> for LogLevel {
+	fn from(level: &str) -> LogLevel {
+		match level {
+			"OFF" => LogLevel::Off,
+			"ERR" => LogLevel::Err,
+			"WARN" => LogLevel::Warn,
+			"INFO" => LogLevel::Info,
+			"DEBUG" => LogLevel::Debug,
+			"TRACE" => LogLevel::Trace,
+			_ => LogLevel::Unknown,
+		}
+	}
+}
+
+bitflags! {
+	/// Logging domains, named as NetworkManager names them.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub struct LogDomains: u64 {
+		const PLATFORM = 1 << 0;
+		const RFKILL = 1 << 1;
+		const ETHER = 1 << 2;
+		const WIFI = 1 << 3;
+		const BT = 1 << 4;
+		const MB = 1 << 5;
+		const DHCP4 = 1 << 6;
+		const DHCP6 = 1 << 7;
+		const PPP = 1 << 8;
+		const WIFI_SCAN = 1 << 9;
+		const IP4 = 1 << 10;
+		const IP6 = 1 << 11;
+		const AUTOIP4 = 1 << 12;
+		const DNS = 1 << 13;
+		const VPN = 1 << 14;
+		const SHARING = 1 << 15;
+		const SUPPLICANT = 1 << 16;
+		const AGENTS = 1 << 17;
+		const SETTINGS = 1 << 18;
+		const SUSPEND = 1 << 19;
+		const CORE = 1 << 20;
+		const DEVICE = 1 << 21;
+		const OLPC = 1 << 22;
+		const INFINIBAND = 1 << 23;
+		const FIREWALL = 1 << 24;
+		const ADSL = 1 << 25;
+		const BOND = 1 << 26;
+		const VLAN = 1 << 27;
+		const BRIDGE = 1 << 28;
+		const DBUS_PROPS = 1 << 29;
+		const TEAM = 1 << 30;
+		const CONCHECK = 1 << 31;
+		const DCB = 1 << 32;
+		const DISPATCH = 1 << 33;
+		const AUDIT = 1 << 34;
+		const SYSTEMD = 1 << 35;
+		const VPN_PLUGIN = 1 << 36;
+		const PROXY = 1 << 37;
+	}
+}
+
+impl LogDomains {
+	/// Parses the comma-separated list returned by `GetLogging`. Per-domain
+	/// level overrides (`DOMAIN:LEVEL`) are dropped, as are unknown domains.
+	pub fn parse(domains: &str) -> LogDomains {
+		domains
+			.split(',')
+			.filter_map(|domain| domain.split(':').next())
+			.filter_map(|domain| LogDomains::from_name(domain.trim()))
+			.collect()
+	}
+
+	/// Formats the domains as the comma-separated list `SetLogging` expects.
+	pub fn to_list(self) -> String {
+		self.iter_names()
+			.map(|(name, _)| name)
+			.collect::<Vec<_>>()
+			.join(",")
+	}
+}
+
+bitflags! {
+	/// What `Reload` should reload. An empty set reloads everything.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub struct ReloadFlags: u32 {
+		const CONF = 0x1;
+		const DNS_RC = 0x2;
+		const DNS_FULL = 0x4;
+	}
+}
+
+bitflags! {
+	/// Flags of a connection profile describing where it comes from.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub struct ConnectionFlags: u32 {
+		/// The profile is not yet saved to disk.
+		const UNSAVED = 0x1;
+		/// The profile was generated by NetworkManager rather than written by a user.
+		const NM_GENERATED = 0x2;
+		/// The profile is deleted once it is no longer active.
+		const VOLATILE = 0x4;
+		/// The profile mirrors a configuration made outside NetworkManager.
+		const EXTERNAL = 0x8;
+	}
+}
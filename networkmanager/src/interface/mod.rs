@@ -28,6 +28,7 @@ pub mod device;
 pub mod enums;
 pub mod settings;
 pub mod statistics;
+pub mod wifi_p2p_peer;
 
 use zbus::proxy;
 
@@ -4,7 +4,11 @@ pub mod access_point;
 pub mod active_connection;
 pub mod config;
 pub mod device;
+pub mod dns;
 pub mod interface;
+pub mod lldp;
 pub mod nm;
 pub mod settings;
 pub(crate) mod util;
+pub mod version;
+pub mod wifi_p2p_peer;
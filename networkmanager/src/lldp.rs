@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::util::take_property;
+use bitflags::bitflags;
+use std::{
+	collections::HashMap,
+	net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+use zbus::zvariant::OwnedValue;
+
+/// A neighbor discovered through LLDP frames received on a device.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LldpNeighbor {
+	pub chassis_id: Option<String>,
+	pub chassis_id_type: ChassisIdType,
+	pub port_id: Option<String>,
+	pub port_id_type: PortIdType,
+	pub port_description: Option<String>,
+	pub system_name: Option<String>,
+	pub system_description: Option<String>,
+	pub system_capabilities: SystemCapabilities,
+	pub management_addresses: Vec<ManagementAddress>,
+	/// The port VLAN id, used for untagged frames.
+	pub pvid: Option<u32>,
+	pub vlans: Vec<LldpVlan>,
+	pub mac_phy_conf: Option<MacPhyConf>,
+	pub power_via_mdi: Option<PowerViaMdi>,
+	pub max_frame_size: Option<u32>,
+	pub mud_url: Option<String>,
+	/// The undecoded LLDP frame.
+	pub raw: Vec<u8>,
+}
+
+impl LldpNeighbor {
+	pub fn new(mut src: HashMap<String, OwnedValue>) -> Self {
+		Self {
+			chassis_id: take_property(&mut src, "chassis-id"),
+			chassis_id_type: take_property::<u32>(&mut src, "chassis-id-type")
+				.map(ChassisIdType::from)
+				.unwrap_or_default(),
+			port_id: take_property(&mut src, "port-id"),
+			port_id_type: take_property::<u32>(&mut src, "port-id-type")
+				.map(PortIdType::from)
+				.unwrap_or_default(),
+			port_description: take_property(&mut src, "port-description"),
+			system_name: take_property(&mut src, "system-name"),
+			system_description: take_property(&mut src, "system-description"),
+			system_capabilities: take_property(&mut src, "system-capabilities")
+				.map(SystemCapabilities::from_bits_truncate)
+				.unwrap_or_else(SystemCapabilities::empty),
+			management_addresses: take_property::<Vec<_>>(&mut src, "management-addresses")
+				.unwrap_or_default()
+				.into_iter()
+				.map(ManagementAddress::new)
+				.collect(),
+			pvid: take_property(&mut src, "ieee-802-1-pvid"),
+			vlans: take_property::<Vec<_>>(&mut src, "ieee-802-1-vlans")
+				.unwrap_or_default()
+				.into_iter()
+				.filter_map(LldpVlan::new)
+				.collect(),
+			mac_phy_conf: take_property(&mut src, "ieee-802-3-mac-phy-conf").map(MacPhyConf::new),
+			power_via_mdi: take_property(&mut src, "ieee-802-3-power-via-mdi")
+				.map(PowerViaMdi::new),
+			max_frame_size: take_property(&mut src, "ieee-802-3-max-frame-size"),
+			mud_url: take_property(&mut src, "mud-url"),
+			raw: take_property(&mut src, "raw").unwrap_or_default(),
+		}
+	}
+}
+
+/// A management address advertised by the neighbor.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ManagementAddress {
+	/// The IANA address family number of `address`.
+	pub address_subtype: u32,
+	pub address: Vec<u8>,
+	pub interface_number_subtype: u32,
+	pub interface_number: u32,
+	pub object_id: Vec<u8>,
+}
+
+impl ManagementAddress {
+	fn new(mut src: HashMap<String, OwnedValue>) -> Self {
+		Self {
+			address_subtype: take_property(&mut src, "address-subtype").unwrap_or_default(),
+			address: take_property(&mut src, "address").unwrap_or_default(),
+			interface_number_subtype: take_property(&mut src, "interface-number-subtype")
+				.unwrap_or_default(),
+			interface_number: take_property(&mut src, "interface-number").unwrap_or_default(),
+			object_id: take_property(&mut src, "object-id").unwrap_or_default(),
+		}
+	}
+
+	/// The address as an IP address, if it is an IPv4 or IPv6 address.
+	pub fn ip(&self) -> Option<IpAddr> {
+		match self.address_subtype {
+			1 => <[u8; 4]>::try_from(self.address.as_slice())
+				.ok()
+				.map(|octets| IpAddr::V4(Ipv4Addr::from(octets))),
+			2 => <[u8; 16]>::try_from(self.address.as_slice())
+				.ok()
+				.map(|octets| IpAddr::V6(Ipv6Addr::from(octets))),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LldpVlan {
+	pub vid: u32,
+	pub name: Option<String>,
+}
+
+impl LldpVlan {
+	fn new(mut src: HashMap<String, OwnedValue>) -> Option<Self> {
+		Some(Self {
+			vid: take_property(&mut src, "vid")?,
+			name: take_property(&mut src, "name"),
+		})
+	}
+}
+
+/// The IEEE 802.3 MAC/PHY configuration and status of the neighbor's port.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MacPhyConf {
+	pub autoneg: u32,
+	pub pmd_autoneg_cap: u32,
+	pub operational_mau_type: u32,
+}
+
+impl MacPhyConf {
+	fn new(mut src: HashMap<String, OwnedValue>) -> Self {
+		Self {
+			autoneg: take_property(&mut src, "autoneg").unwrap_or_default(),
+			pmd_autoneg_cap: take_property(&mut src, "pmd-autoneg-cap").unwrap_or_default(),
+			operational_mau_type: take_property(&mut src, "operational-mau-type")
+				.unwrap_or_default(),
+		}
+	}
+}
+
+/// The IEEE 802.3 power via MDI (PoE) capabilities of the neighbor's port.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PowerViaMdi {
+	pub mdi_power_support: u32,
+	pub pse_power_pair: u32,
+	pub power_class: u32,
+}
+
+impl PowerViaMdi {
+	fn new(mut src: HashMap<String, OwnedValue>) -> Self {
+		Self {
+			mdi_power_support: take_property(&mut src, "mdi-power-support").unwrap_or_default(),
+			pse_power_pair: take_property(&mut src, "pse-power-pair").unwrap_or_default(),
+			power_class: take_property(&mut src, "power-class").unwrap_or_default(),
+		}
+	}
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChassisIdType {
+	ChassisComponent,
+	InterfaceAlias,
+	PortComponent,
+	MacAddress,
+	NetworkAddress,
+	InterfaceName,
+	LocallyAssigned,
+	#[default]
+	Unknown,
+}
+
+impl From<u32> for ChassisIdType {
+	fn from(subtype: u32) -> ChassisIdType {
+		match subtype {
+			1 => ChassisIdType::ChassisComponent,
+			2 => ChassisIdType::InterfaceAlias,
+			3 => ChassisIdType::PortComponent,
+			4 => ChassisIdType::MacAddress,
+			5 => ChassisIdType::NetworkAddress,
+			6 => ChassisIdType::InterfaceName,
+			7 => ChassisIdType::LocallyAssigned,
+			_ => ChassisIdType::Unknown,
+		}
+	}
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortIdType {
+	InterfaceAlias,
+	PortComponent,
+	MacAddress,
+	NetworkAddress,
+	InterfaceName,
+	AgentCircuitId,
+	LocallyAssigned,
+	#[default]
+	Unknown,
+}
+
+impl From<u32> for PortIdType {
+	fn from(subtype: u32) -> PortIdType {
+		match subtype {
+			1 => PortIdType::InterfaceAlias,
+			2 => PortIdType::PortComponent,
+			3 => PortIdType::MacAddress,
+			4 => PortIdType::NetworkAddress,
+			5 => PortIdType::InterfaceName,
+			6 => PortIdType::AgentCircuitId,
+			7 => PortIdType::LocallyAssigned,
+			_ => PortIdType::Unknown,
+		}
+	}
+}
+
+bitflags! {
+	#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+	pub struct SystemCapabilities: u32 {
+		const OTHER = 0x1;
+		const REPEATER = 0x2;
+		const MAC_BRIDGE = 0x4;
+		const WLAN_AP = 0x8;
+		const ROUTER = 0x10;
+		const TELEPHONE = 0x20;
+		const DOCSIS = 0x40;
+		const STATION = 0x80;
+		const CVLAN = 0x100;
+		const SVLAN = 0x200;
+		const TPMR = 0x400;
+	}
+}
@@ -2,17 +2,31 @@
 
 use crate::{
 	active_connection::ActiveConnection,
-	device::Device,
+	device::{Device, DeviceSnapshot, modem::ModemDevice, wifi_p2p::WifiP2PDevice},
+	dns::DnsSummary,
 	interface::{
 		NetworkManagerProxy,
 		active_connection::ActiveConnectionProxy,
 		device::DeviceProxy,
-		enums::{NmConnectivityState, NmState},
+		enums::{LogDomains, LogLevel, NmConnectivityState, NmMetered, NmState, ReloadFlags},
+		settings::connection::ConnectionSettingsProxy,
 	},
-	settings::{NetworkManagerSettings, connection::Connection},
+	settings::{
+		NetworkManagerSettings,
+		connection::{
+			Connection, MobileBroadbandSettings, Settings, WwwProxySettings, enums::ProxyMethod,
+		},
+	},
+	util::{build_proxies, settings_ref},
+	version::Version,
+	wifi_p2p_peer::WifiP2PPeer,
 };
+use futures_util::{Stream, StreamExt};
 use std::ops::Deref;
-use zbus::{Result, zvariant::ObjectPath};
+use zbus::{
+	Result,
+	zvariant::{ObjectPath, OwnedObjectPath},
+};
 
 #[derive(Debug)]
 pub struct NetworkManager<'a>(NetworkManagerProxy<'a>);
@@ -59,23 +73,195 @@ impl<'a> NetworkManager<'a> {
 			.map(ActiveConnection::from)
 	}
 
+	/// Add a new connection profile and activate it on the given device.
+	pub async fn add_and_activate_connection(
+		&self,
+		settings: &Settings,
+		device: &Device<'_>,
+	) -> Result<(Connection<'a>, ActiveConnection<'a>)> {
+		self.add_and_activate_connection_by_path(settings, device.inner().path())
+			.await
+	}
+
+	/// Add a new connection profile and activate it on the device at the given object path.
+	pub async fn add_and_activate_connection_by_path(
+		&self,
+		settings: &Settings,
+		device: &ObjectPath<'_>,
+	) -> Result<(Connection<'a>, ActiveConnection<'a>)> {
+		let specific_object = ObjectPath::from_static_str("/").unwrap();
+		self.add_and_activate(settings, device, &specific_object)
+			.await
+	}
+
+	/// Create a Wi-Fi P2P profile for the peer and connect to it.
+	pub async fn add_and_activate_wifi_p2p(
+		&self,
+		device: &WifiP2PDevice<'_>,
+		peer: &WifiP2PPeer<'_>,
+		wfd_ies: Option<Vec<u8>>,
+	) -> Result<(Connection<'a>, ActiveConnection<'a>)> {
+		let settings = Settings::wifi_p2p(peer.hw_address().await?, wfd_ies);
+		self.add_and_activate(&settings, device.inner().path(), peer.inner().path())
+			.await
+	}
+
+	async fn add_and_activate(
+		&self,
+		settings: &Settings,
+		device: &ObjectPath<'_>,
+		specific_object: &ObjectPath<'_>,
+	) -> Result<(Connection<'a>, ActiveConnection<'a>)> {
+		let settings = settings.build();
+		let (connection_path, active_connection_path) = self
+			.0
+			.add_and_activate_connection(settings_ref(&settings), device, specific_object)
+			.await?;
+		let connection = ConnectionSettingsProxy::builder(self.0.inner().connection())
+			.path(connection_path)?
+			.build()
+			.await?;
+		let active_connection = ActiveConnectionProxy::builder(self.0.inner().connection())
+			.path(active_connection_path)?
+			.build()
+			.await?;
+		Ok((connection.into(), active_connection.into()))
+	}
+
+	/// Create a mobile broadband profile for the modem and activate it.
+	pub async fn add_and_activate_mobile_broadband(
+		&self,
+		device: &ModemDevice<'_>,
+		id: &str,
+		broadband: MobileBroadbandSettings,
+	) -> Result<(Connection<'a>, ActiveConnection<'a>)> {
+		let settings = Settings::mobile_broadband(id, broadband);
+		self.add_and_activate_connection_by_path(&settings, device.inner().path())
+			.await
+	}
+
 	pub async fn active_connections(&self) -> Result<Vec<ActiveConnection<'a>>> {
 		let active_connections = self.0.active_connections().await?;
-		let mut out = Vec::with_capacity(active_connections.len());
-		for active_connection in active_connections {
-			let active_connection = ActiveConnectionProxy::builder(self.0.inner().connection())
-				.path(active_connection)?
-				.build()
-				.await?;
-			out.push(active_connection.into());
-		}
-		Ok(out)
+		let proxies: Vec<ActiveConnectionProxy> =
+			build_proxies(self.0.inner().connection(), active_connections).await?;
+		Ok(proxies.into_iter().map(Into::into).collect())
 	}
 
 	pub async fn connectivity(&self) -> Result<NmConnectivityState> {
 		self.0.connectivity().await.map(NmConnectivityState::from)
 	}
 
+	/// Watch connectivity changes. Transitions into [`NmConnectivityState::Portal`]
+	/// carry the URI used for the connectivity check, which is where a captive
+	/// portal login should be opened.
+	pub async fn connectivity_transitions(
+		&self,
+	) -> Result<impl Stream<Item = ConnectivityTransition> + '_> {
+		let mut previous = self.connectivity().await?;
+		let changes = self.0.receive_connectivity_changed().await;
+		Ok(changes
+			.filter_map(|change| async move { change.get().await.ok() })
+			.map(NmConnectivityState::from)
+			.filter_map(move |current| {
+				let from = previous;
+				previous = current;
+				async move {
+					if from == current {
+						return None;
+					}
+					let portal_uri = if current == NmConnectivityState::Portal {
+						self.0.connectivity_check_uri().await.ok()
+					} else {
+						None
+					};
+					Some(ConnectivityTransition {
+						from,
+						to: current,
+						portal_uri,
+					})
+				}
+			}))
+	}
+
+	/// The proxy configuration of the primary connection. Without a primary
+	/// connection, or if it has no proxy setting, the method is [`ProxyMethod::None`].
+	pub async fn effective_proxy(&self) -> Result<WwwProxySettings> {
+		let primary_connection = self.0.primary_connection().await?;
+		self.proxy_of(primary_connection).await
+	}
+
+	/// Watch the effective proxy configuration. It is fetched again whenever the
+	/// primary connection changes or the settings profile of the primary
+	/// connection is updated.
+	pub async fn effective_proxy_changes(
+		&self,
+	) -> Result<impl Stream<Item = Result<WwwProxySettings>> + '_> {
+		let primary_changed = self
+			.0
+			.receive_primary_connection_changed()
+			.await
+			.map(|_| None);
+		let rule = zbus::MatchRule::builder()
+			.msg_type(zbus::message::Type::Signal)
+			.sender("org.freedesktop.NetworkManager")?
+			.interface("org.freedesktop.NetworkManager.Settings.Connection")?
+			.member("Updated")?
+			.path_namespace("/org/freedesktop/NetworkManager/Settings")?
+			.build();
+		let profile_updated =
+			zbus::MessageStream::for_match_rule(rule, self.0.inner().connection(), None)
+				.await?
+				.filter_map(|message| async move {
+					let message = message.ok()?;
+					let path = OwnedObjectPath::from(message.header().path()?.to_owned());
+					Some(Some(path))
+				});
+		Ok(
+			futures_util::stream::select(primary_changed, profile_updated).filter_map(
+				move |updated_profile| async move {
+					if let Some(updated_profile) = updated_profile {
+						match self.primary_profile().await {
+							Ok(Some(primary_profile)) if primary_profile == updated_profile => (),
+							Ok(_) => return None,
+							Err(why) => return Some(Err(why)),
+						}
+					}
+					Some(self.effective_proxy().await)
+				},
+			),
+		)
+	}
+
+	/// The object path of the settings profile of the primary connection.
+	async fn primary_profile(&self) -> Result<Option<OwnedObjectPath>> {
+		let primary_connection = self.0.primary_connection().await?;
+		if primary_connection.as_str() == "/" {
+			return Ok(None);
+		}
+		let active_connection = ActiveConnectionProxy::builder(self.0.inner().connection())
+			.path(primary_connection)?
+			.build()
+			.await?;
+		active_connection.connection_().await.map(Some)
+	}
+
+	async fn proxy_of(&self, active_connection: OwnedObjectPath) -> Result<WwwProxySettings> {
+		let mut proxy = if active_connection.as_str() == "/" {
+			WwwProxySettings::default()
+		} else {
+			let active_connection: ActiveConnection =
+				ActiveConnectionProxy::builder(self.0.inner().connection())
+					.path(active_connection)?
+					.build()
+					.await?
+					.into();
+			let settings = active_connection.connection().await?.settings().await?;
+			settings.proxy.unwrap_or_default()
+		};
+		proxy.method.get_or_insert(ProxyMethod::None);
+		Ok(proxy)
+	}
+
 	pub async fn check_connectivity(&self) -> Result<NmConnectivityState> {
 		self.0
 			.check_connectivity()
@@ -89,30 +275,130 @@ impl<'a> NetworkManager<'a> {
 			.await
 	}
 
+	/// Realized devices, those that currently exist in the kernel.
 	pub async fn devices(&self) -> Result<Vec<Device<'a>>> {
-		let devices = self.0.get_all_devices().await?;
-		let mut out = Vec::with_capacity(devices.len());
-		for device in devices {
-			let device = DeviceProxy::builder(self.0.inner().connection())
-				.path(device)?
-				.build()
-				.await?;
-			out.push(device.into());
-		}
-		Ok(out)
+		let devices = self.0.get_devices().await?;
+		let proxies: Vec<DeviceProxy> = build_proxies(self.0.inner().connection(), devices).await?;
+		Ok(proxies.into_iter().map(Into::into).collect())
 	}
 
+	/// All devices, including placeholders for software devices which will be
+	/// created when one of their connection profiles is activated.
 	pub async fn all_devices(&self) -> Result<Vec<Device<'a>>> {
 		let devices = self.0.get_all_devices().await?;
-		let mut out = Vec::with_capacity(devices.len());
-		for device in devices {
-			let device = DeviceProxy::builder(self.0.inner().connection())
-				.path(device)?
-				.build()
+		let proxies: Vec<DeviceProxy> = build_proxies(self.0.inner().connection(), devices).await?;
+		Ok(proxies.into_iter().map(Into::into).collect())
+	}
+
+	/// Realized devices which NetworkManager manages.
+	pub async fn managed_devices(&self) -> Result<Vec<Device<'a>>> {
+		let devices = self.devices().await?;
+		let managed =
+			futures_util::future::try_join_all(devices.iter().map(|device| device.is_managed()))
 				.await?;
-			out.push(device.into());
-		}
-		Ok(out)
+		Ok(devices
+			.into_iter()
+			.zip(managed)
+			.filter_map(|(device, managed)| managed.then_some(device))
+			.collect())
+	}
+
+	/// Look up a realized device by its IP interface name, returning `None` if
+	/// no device has it.
+	pub async fn device_by_interface(&self, interface: &str) -> Result<Option<Device<'a>>> {
+		let path = match self.0.get_device_by_ip_iface(interface).await {
+			Ok(path) => path,
+			Err(zbus::Error::MethodError(name, _, _))
+				if name.as_str() == "org.freedesktop.NetworkManager.UnknownDevice" =>
+			{
+				return Ok(None);
+			}
+			Err(why) => return Err(why),
+		};
+		DeviceProxy::builder(self.0.inner().connection())
+			.path(path)?
+			.build()
+			.await
+			.map(|device| Some(device.into()))
+	}
+
+	/// Fetch the properties of every device concurrently, one `GetAll` call per
+	/// device, without building a [`Device`] for each.
+	pub async fn device_snapshots(&self) -> Result<Vec<DeviceSnapshot>> {
+		let devices = self.0.get_all_devices().await?;
+		DeviceSnapshot::fetch_all(self.0.inner().connection(), devices).await
+	}
+
+	/// The effective DNS configuration across all active connections and the
+	/// global DNS configuration.
+	pub async fn dns_summary(&self) -> Result<DnsSummary> {
+		let (active_connections, global) = futures_util::future::try_join(
+			self.0.active_connections(),
+			self.0.global_dns_configuration(),
+		)
+		.await?;
+		DnsSummary::fetch(self.0.inner().connection(), active_connections, global).await
+	}
+
+	/// The current global log level and the domains being logged. Per-domain
+	/// level overrides and domains unknown to [`LogDomains`] are not represented;
+	/// use [`Self::raise_logging`] to save a configuration for restoring.
+	pub async fn logging(&self) -> Result<Logging> {
+		let (level, domains) = self.0.get_logging().await?;
+		Ok(Logging {
+			level: LogLevel::from(level.as_str()),
+			domains: LogDomains::parse(&domains),
+		})
+	}
+
+	/// Set the global log level and domains. An empty domain set leaves the
+	/// logged domains unchanged.
+	pub async fn set_logging(&self, logging: Logging) -> Result<()> {
+		self.0
+			.set_logging(logging.level.as_str(), &logging.domains.to_list())
+			.await
+	}
+
+	/// Raise logging to `logging`, returning the previous configuration so that
+	/// it can be restored exactly with [`Self::restore_logging`].
+	pub async fn raise_logging(&self, logging: Logging) -> Result<PreviousLogging> {
+		let (level, domains) = self.0.get_logging().await?;
+		self.set_logging(logging).await?;
+		Ok(PreviousLogging { level, domains })
+	}
+
+	/// Restore the logging configuration returned by [`Self::raise_logging`].
+	pub async fn restore_logging(&self, previous: PreviousLogging) -> Result<()> {
+		self.0.set_logging(&previous.level, &previous.domains).await
+	}
+
+	/// Reload the parts of the daemon configuration selected by `flags`.
+	pub async fn reload(&self, flags: ReloadFlags) -> Result<()> {
+		self.0.reload(flags.bits()).await
+	}
+
+	/// Enable or disable networking as a whole. Disabling deactivates every
+	/// connection and keeps devices down until networking is enabled again.
+	pub async fn set_networking_enabled(&self, enabled: bool) -> Result<()> {
+		self.0.enable(enabled).await
+	}
+
+	/// Put NetworkManager to sleep or wake it up, as around a system suspend.
+	/// Unlike disabling networking this is not persisted across restarts, and
+	/// it fails if the daemon is already in the requested state.
+	pub async fn set_sleeping(&self, sleeping: bool) -> Result<()> {
+		self.0.sleep(sleeping).await
+	}
+
+	pub async fn version(&self) -> Result<Version> {
+		let version = self.0.version().await?;
+		version
+			.parse::<Version>()
+			.map_err(|why| zbus::Error::Failure(why.to_string()))
+	}
+
+	pub async fn metered(&self) -> Result<NmMetered> {
+		self.0.metered().await.map(NmMetered::from)
 	}
 
 	pub async fn state(&self) -> Result<NmState> {
@@ -123,3 +409,25 @@ impl<'a> NetworkManager<'a> {
 		NetworkManagerSettings::new(self.0.inner().connection()).await
 	}
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectivityTransition {
+	pub from: NmConnectivityState,
+	pub to: NmConnectivityState,
+	/// The connectivity check URI, set when entering [`NmConnectivityState::Portal`].
+	pub portal_uri: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Logging {
+	pub level: LogLevel,
+	pub domains: LogDomains,
+}
+
+/// A logging configuration saved by [`NetworkManager::raise_logging`], kept as
+/// NetworkManager reported it, including per-domain `DOMAIN:LEVEL` overrides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviousLogging {
+	level: String,
+	domains: String,
+}
@@ -1,9 +1,15 @@
 // SPDX-License-Identifier: MPL-2.0
 
 pub mod connection;
+pub mod controller;
+pub mod store;
 
-use self::connection::Connection;
-use crate::interface::settings::{SettingsProxy, connection::ConnectionSettingsProxy};
+use self::connection::{Connection, Settings};
+use crate::{
+	device::Device,
+	interface::settings::{SettingsProxy, connection::ConnectionSettingsProxy},
+	util::{build_proxies, join_existing, parse_hw_address, settings_ref},
+};
 use std::ops::Deref;
 use zbus::Result;
 
@@ -25,14 +31,108 @@ impl<'a> NetworkManagerSettings<'a> {
 
 	pub async fn list_connections(&'a self) -> Result<Vec<Connection<'a>>> {
 		let connections = self.0.list_connections().await?;
-		let mut out = Vec::with_capacity(connections.len());
-		for connection in connections {
-			let connection = ConnectionSettingsProxy::builder(self.0.inner().connection())
-				.path(connection)?
-				.build()
-				.await?;
-			out.push(connection.into());
-		}
-		Ok(out)
+		let proxies: Vec<ConnectionSettingsProxy> =
+			build_proxies(self.0.inner().connection(), connections).await?;
+		Ok(proxies.into_iter().map(Into::into).collect())
+	}
+
+	/// Add a new connection profile and save it to disk.
+	pub async fn add_connection(&'a self, settings: &Settings) -> Result<Connection<'a>> {
+		let settings = settings.build();
+		let path = self.0.add_connection(settings_ref(&settings)).await?;
+		ConnectionSettingsProxy::builder(self.0.inner().connection())
+			.path(path)?
+			.build()
+			.await
+			.map(Connection::from)
+	}
+
+	/// Look up a connection profile by its UUID, returning `None` if no profile has it.
+	pub async fn connection_by_uuid(&'a self, uuid: &str) -> Result<Option<Connection<'a>>> {
+		let path = match self.0.get_connection_by_uuid(uuid).await {
+			Ok(path) => path,
+			Err(zbus::Error::MethodError(name, _, _))
+				if name.as_str() == "org.freedesktop.NetworkManager.Settings.InvalidConnection" =>
+			{
+				return Ok(None);
+			}
+			Err(why) => return Err(why),
+		};
+		ConnectionSettingsProxy::builder(self.0.inner().connection())
+			.path(path)?
+			.build()
+			.await
+			.map(|connection| Some(connection.into()))
+	}
+
+	/// All connection profiles whose `connection.id` equals `id`.
+	pub async fn connections_by_id(&'a self, id: &str) -> Result<Vec<Connection<'a>>> {
+		self.connections_matching(|settings| {
+			settings
+				.connection
+				.as_ref()
+				.is_some_and(|connection| connection.id.as_deref() == Some(id))
+		})
+		.await
+	}
+
+	/// All Wi-Fi connection profiles for the given SSID.
+	pub async fn connections_for_ssid(&'a self, ssid: &[u8]) -> Result<Vec<Connection<'a>>> {
+		self.connections_matching(|settings| {
+			settings
+				.wifi
+				.as_ref()
+				.is_some_and(|wifi| wifi.ssid.as_deref() == Some(ssid))
+		})
+		.await
+	}
+
+	/// All connection profiles bound to the given device, either by its interface
+	/// name or by its permanent hardware address.
+	pub async fn connections_for_device(
+		&'a self,
+		device: &Device<'_>,
+	) -> Result<Vec<Connection<'a>>> {
+		let interface = device.interface().await?;
+		let hw_address = parse_hw_address(&device.permanent_hw_address().await?);
+		self.connections_matching(|settings| {
+			let by_interface = settings
+				.connection
+				.as_ref()
+				.and_then(|connection| connection.interface_name.as_deref())
+				.is_some_and(|name| name == interface);
+			let by_mac = hw_address.as_deref().is_some_and(|hw_address| {
+				let ethernet = settings
+					.ethernet
+					.as_ref()
+					.and_then(|ethernet| ethernet.mac_address.as_deref());
+				let wifi = settings
+					.wifi
+					.as_ref()
+					.and_then(|wifi| wifi.mac_address.as_deref());
+				ethernet == Some(hw_address) || wifi == Some(hw_address)
+			});
+			by_interface || by_mac
+		})
+		.await
+	}
+
+	/// Fetch the settings of every connection profile concurrently and keep those
+	/// accepted by `filter`. Profiles deleted in the meantime are left out.
+	async fn connections_matching(
+		&'a self,
+		filter: impl Fn(&Settings) -> bool,
+	) -> Result<Vec<Connection<'a>>> {
+		let connections = self.list_connections().await?;
+		let connections = join_existing(connections.into_iter().map(|connection| async move {
+			let settings = connection.settings().await?;
+			Ok((connection, settings))
+		}))
+		.await?;
+
+		Ok(connections
+			.into_iter()
+			.filter_map(|(connection, settings)| filter(&settings).then_some(connection))
+			.collect())
 	}
 }
@@ -1,6 +1,17 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::interface::settings::connection::ConnectionSettingsProxy;
+pub mod diff;
+pub mod enums;
+
+use self::enums::{
+	BondMode, CertificateSource, ConnectionMetered, ConnectionType, EapMethods, Ipv4Method,
+	Ipv6AddrGenMode, Ipv6Method, Ipv6Privacy, KeyMgmt, LldpMode, LlmnrMode, MdnsMode, MultiConnect,
+	Phase2Auth, PortType, ProxyMethod, SecretFlags, WifiConnectionMode,
+};
+use crate::{
+	interface::settings::connection::ConnectionSettingsProxy,
+	util::{owned_settings_to_values, settings_ref},
+};
 use derive_builder::Builder;
 use std::{collections::HashMap, ops::Deref};
 
@@ -15,6 +26,43 @@ impl<'a> Deref for Connection<'a> {
 	}
 }
 
+impl Connection<'_> {
+	/// Fetch the settings of this connection profile, without secrets.
+	pub async fn settings(&self) -> zbus::Result<Settings> {
+		self.0.get_settings().await.map(Settings::new)
+	}
+
+	/// Set whether this connection profile is metered, leaving all of its other
+	/// settings untouched.
+	pub async fn set_metered(&self, metered: ConnectionMetered) -> zbus::Result<()> {
+		self.update_property("connection", "metered", metered.into())
+			.await
+	}
+
+	/// Set whether LLDP frames are received on this connection, leaving all of
+	/// its other settings untouched.
+	pub async fn set_lldp(&self, lldp: LldpMode) -> zbus::Result<()> {
+		self.update_property("connection", "lldp", lldp.into())
+			.await
+	}
+
+	/// Replace a single property of the profile. `Update` replaces the whole
+	/// profile, so the current settings are fetched and sent back with it.
+	async fn update_property(
+		&self,
+		setting: &str,
+		key: &str,
+		value: zbus::zvariant::Value<'static>,
+	) -> zbus::Result<()> {
+		let mut settings = owned_settings_to_values(self.0.get_settings().await?);
+		settings
+			.entry(setting.to_owned())
+			.or_default()
+			.insert(key.to_owned(), value);
+		self.0.update(settings_ref(&settings)).await
+	}
+}
+
 impl<'a> From<ConnectionSettingsProxy<'a>> for Connection<'a> {
 	fn from(conn: ConnectionSettingsProxy<'a>) -> Self {
 		Connection(conn)
@@ -66,6 +114,26 @@ pub struct Settings {
 	#[builder(setter(strip_option))]
 	pub bluetooth: Option<BluetoothSettings>,
 	#[builder(setter(strip_option))]
+	pub ieee8021x: Option<Ieee8021xSettings>,
+	#[builder(setter(strip_option))]
+	pub gsm: Option<GsmSettings>,
+	#[builder(setter(strip_option))]
+	pub cdma: Option<CdmaSettings>,
+	#[builder(setter(strip_option))]
+	pub bond: Option<BondSettings>,
+	#[builder(setter(strip_option))]
+	pub bridge: Option<BridgeSettings>,
+	#[builder(setter(strip_option))]
+	pub bridge_port: Option<BridgePortSettings>,
+	#[builder(setter(strip_option))]
+	pub team: Option<TeamSettings>,
+	#[builder(setter(strip_option))]
+	pub team_port: Option<TeamPortSettings>,
+	#[builder(setter(strip_option))]
+	pub vlan: Option<VlanSettings>,
+	#[builder(setter(strip_option))]
+	pub wifi_p2p: Option<WifiP2PSettings>,
+	#[builder(setter(strip_option))]
 	pub ipv4: Option<Ipv4Settings>,
 	#[builder(setter(strip_option))]
 	pub ipv6: Option<Ipv6Settings>,
@@ -74,6 +142,112 @@ pub struct Settings {
 }
 
 impl Settings {
+	/// Settings for a new mobile broadband profile with automatic IP configuration.
+	pub fn mobile_broadband(id: impl Into<String>, broadband: MobileBroadbandSettings) -> Self {
+		let (type_, gsm, cdma) = match broadband {
+			MobileBroadbandSettings::Gsm(gsm) => (ConnectionType::Gsm, Some(gsm), None),
+			MobileBroadbandSettings::Cdma(cdma) => (ConnectionType::Cdma, None, Some(cdma)),
+		};
+		Self {
+			connection: Some(ConnectionSettings {
+				id: Some(id.into()),
+				type_: Some(type_),
+				autoconnect: Some(true),
+				..Default::default()
+			}),
+			gsm,
+			cdma,
+			ipv4: Some(Ipv4Settings {
+				method: Some(Ipv4Method::Auto),
+				..Default::default()
+			}),
+			ipv6: Some(Ipv6Settings {
+				method: Some(Ipv6Method::Auto),
+				..Default::default()
+			}),
+			..Default::default()
+		}
+	}
+
+	/// Settings for a new bond interface with automatic IP configuration.
+	pub fn bond(interface_name: impl Into<String>, bond: BondSettings) -> Self {
+		Self {
+			bond: Some(bond),
+			..Self::virtual_interface(ConnectionType::Bond, interface_name.into())
+		}
+	}
+
+	/// Settings for a new bridge interface with automatic IP configuration.
+	pub fn bridge(interface_name: impl Into<String>, bridge: BridgeSettings) -> Self {
+		Self {
+			bridge: Some(bridge),
+			..Self::virtual_interface(ConnectionType::Bridge, interface_name.into())
+		}
+	}
+
+	/// Settings for a new VLAN interface on top of `parent`, which is either an
+	/// interface name or the UUID of the parent's profile.
+	pub fn vlan(interface_name: impl Into<String>, parent: impl Into<String>, id: u32) -> Self {
+		Self {
+			vlan: Some(VlanSettings {
+				parent: Some(parent.into()),
+				id: Some(id),
+				..Default::default()
+			}),
+			..Self::virtual_interface(ConnectionType::Vlan, interface_name.into())
+		}
+	}
+
+	/// Settings for a new Wi-Fi P2P profile connecting to the peer with the
+	/// given hardware address, optionally advertising Wi-Fi Display information
+	/// elements as needed for Miracast.
+	pub fn wifi_p2p(peer_hw_address: impl Into<String>, wfd_ies: Option<Vec<u8>>) -> Self {
+		let peer = peer_hw_address.into();
+		Self {
+			connection: Some(ConnectionSettings {
+				id: Some(format!("Wi-Fi P2P {peer}")),
+				type_: Some(ConnectionType::WifiP2P),
+				autoconnect: Some(false),
+				..Default::default()
+			}),
+			wifi_p2p: Some(WifiP2PSettings {
+				peer: Some(peer),
+				wfd_ies,
+				..Default::default()
+			}),
+			ipv4: Some(Ipv4Settings {
+				method: Some(Ipv4Method::Auto),
+				..Default::default()
+			}),
+			ipv6: Some(Ipv6Settings {
+				method: Some(Ipv6Method::Auto),
+				..Default::default()
+			}),
+			..Default::default()
+		}
+	}
+
+	fn virtual_interface(type_: ConnectionType, interface_name: String) -> Self {
+		Self {
+			connection: Some(ConnectionSettings {
+				id: Some(interface_name.clone()),
+				interface_name: Some(interface_name),
+				type_: Some(type_),
+				autoconnect: Some(true),
+				..Default::default()
+			}),
+			ipv4: Some(Ipv4Settings {
+				method: Some(Ipv4Method::Auto),
+				..Default::default()
+			}),
+			ipv6: Some(Ipv6Settings {
+				method: Some(Ipv6Method::Auto),
+				..Default::default()
+			}),
+			..Default::default()
+		}
+	}
+
 	pub fn new(
 		mut src: std::collections::HashMap<
 			String,
@@ -85,6 +259,16 @@ impl Settings {
 			ethernet: src.remove("802-3-ethernet").map(EthernetSettings::new),
 			wifi: src.remove("802-11-wireless").map(WifiSettings::new),
 			bluetooth: src.remove("bluetooth").map(BluetoothSettings::new),
+			ieee8021x: src.remove("802-1x").map(Ieee8021xSettings::new),
+			gsm: src.remove("gsm").map(GsmSettings::new),
+			cdma: src.remove("cdma").map(CdmaSettings::new),
+			bond: src.remove("bond").map(BondSettings::new),
+			bridge: src.remove("bridge").map(BridgeSettings::new),
+			bridge_port: src.remove("bridge-port").map(BridgePortSettings::new),
+			team: src.remove("team").map(TeamSettings::new),
+			team_port: src.remove("team-port").map(TeamPortSettings::new),
+			vlan: src.remove("vlan").map(VlanSettings::new),
+			wifi_p2p: src.remove("wifi-p2p").map(WifiP2PSettings::new),
 			ipv4: src.remove("ipv4").map(Ipv4Settings::new),
 			ipv6: src.remove("ipv6").map(Ipv6Settings::new),
 			proxy: src.remove("proxy").map(WwwProxySettings::new),
@@ -110,6 +294,36 @@ impl Settings {
 		if let Some(val) = &self.bluetooth {
 			out.insert("bluetooth".into(), val.build());
 		}
+		if let Some(val) = &self.ieee8021x {
+			out.insert("802-1x".into(), val.build());
+		}
+		if let Some(val) = &self.gsm {
+			out.insert("gsm".into(), val.build());
+		}
+		if let Some(val) = &self.cdma {
+			out.insert("cdma".into(), val.build());
+		}
+		if let Some(val) = &self.bond {
+			out.insert("bond".into(), val.build());
+		}
+		if let Some(val) = &self.bridge {
+			out.insert("bridge".into(), val.build());
+		}
+		if let Some(val) = &self.bridge_port {
+			out.insert("bridge-port".into(), val.build());
+		}
+		if let Some(val) = &self.team {
+			out.insert("team".into(), val.build());
+		}
+		if let Some(val) = &self.team_port {
+			out.insert("team-port".into(), val.build());
+		}
+		if let Some(val) = &self.vlan {
+			out.insert("vlan".into(), val.build());
+		}
+		if let Some(val) = &self.wifi_p2p {
+			out.insert("wifi-p2p".into(), val.build());
+		}
 		if let Some(val) = &self.ipv4 {
 			out.insert("ipv4".into(), val.build());
 		}
@@ -132,18 +346,21 @@ derive_value_build!(
 	(gateway_ping_timeout("gateway-ping-timeout"): u32),
 	(id("id"): String),
 	(interface_name("interface-name"): String),
-	(lldp("lldp"): i32),
-	(llmnr("llmnr"): i32),
+	(lldp("lldp"): LldpMode),
+	(llmnr("llmnr"): LlmnrMode),
 	(master("master"): String),
-	(mdns("mdns"): i32),
+	(mdns("mdns"): MdnsMode),
+	(metered("metered"): ConnectionMetered),
 	(mud_url("mud_url"): String),
-	(multi_connect("multi-connect"): String),
+	(multi_connect("multi-connect"): MultiConnect),
 	(permissions("permissions"): Vec<String>),
+	(port_type("port-type"): PortType),
 	(timestamp("timestamp"): u64),
 	(read_only("read-only"): bool),
 	(secondaries("secondaries"): Vec<String>),
+	(slave_type("slave-type"): PortType),
 	(stable_id("stable-id"): String),
-	(type_("type"): String),
+	(type_("type"): ConnectionType),
 	(uuid("uuid"): String),
 	(wait_device_timeout("wait-device-timeout"): i32),
 	(zone("zone"): String)
@@ -182,7 +399,7 @@ derive_value_build!(
 	(mac_address("mac-address"): Vec<u8>),
 	(mac_address_blacklist("mac-address-blacklist"): Vec<String>),
 	(mac_address_randomization("mac-address-randomization"): u32),
-	(mode("mode"): String),
+	(mode("mode"): WifiConnectionMode),
 	(mtu("mtu"): u32),
 	(powersave("powersave"): u32),
 	(rate("rate"): u32),
@@ -198,6 +415,106 @@ derive_value_build!(
 	(type_("type"): String)
 );
 
+derive_value_build!(
+	GsmSettings,
+	(apn("apn"): String),
+	(auto_config("auto-config"): bool),
+	(device_id("device-id"): String),
+	(home_only("home-only"): bool),
+	(initial_eps_bearer_apn("initial-eps-bearer-apn"): String),
+	(initial_eps_bearer_configure("initial-eps-bearer-configure"): bool),
+	(mtu("mtu"): u32),
+	(network_id("network-id"): String),
+	(password("password"): String),
+	(password_flags("password-flags"): SecretFlags),
+	(pin("pin"): String),
+	(pin_flags("pin-flags"): SecretFlags),
+	(sim_id("sim-id"): String),
+	(sim_operator_id("sim-operator-id"): String),
+	(username("username"): String)
+);
+
+/// Access technology specific settings of a mobile broadband profile.
+#[derive(Debug, Clone)]
+pub enum MobileBroadbandSettings {
+	Gsm(GsmSettings),
+	Cdma(CdmaSettings),
+}
+
+derive_value_build!(
+	CdmaSettings,
+	(mtu("mtu"): u32),
+	(number("number"): String),
+	(password("password"): String),
+	(password_flags("password-flags"): SecretFlags),
+	(username("username"): String)
+);
+
+derive_value_build!(BondSettings, (options("options"): HashMap<String, String>));
+
+impl BondSettings {
+	pub fn mode(&self) -> Option<BondMode> {
+		self.options
+			.as_ref()
+			.and_then(|options| options.get("mode"))
+			.map(|mode| BondMode::from_raw(mode))
+	}
+
+	pub fn set_mode(&mut self, mode: BondMode) {
+		self.set_option("mode", mode.as_str());
+	}
+
+	/// Set a bonding driver option such as `miimon` or `lacp_rate`.
+	pub fn set_option(&mut self, key: impl Into<String>, value: impl Into<String>) {
+		self.options
+			.get_or_insert_with(HashMap::new)
+			.insert(key.into(), value.into());
+	}
+}
+
+derive_value_build!(
+	BridgeSettings,
+	(ageing_time("ageing-time"): u32),
+	(forward_delay("forward-delay"): u32),
+	(group_forward_mask("group-forward-mask"): u32),
+	(hello_time("hello-time"): u32),
+	(mac_address("mac-address"): Vec<u8>),
+	(max_age("max-age"): u32),
+	(multicast_snooping("multicast-snooping"): bool),
+	(priority("priority"): u32),
+	(stp("stp"): bool),
+	(vlan_default_pvid("vlan-default-pvid"): u32),
+	(vlan_filtering("vlan-filtering"): bool)
+);
+
+derive_value_build!(
+	BridgePortSettings,
+	(hairpin_mode("hairpin-mode"): bool),
+	(path_cost("path-cost"): u32),
+	(priority("priority"): u32)
+);
+
+derive_value_build!(TeamSettings, (config("config"): String));
+
+derive_value_build!(TeamPortSettings, (config("config"): String));
+
+derive_value_build!(
+	VlanSettings,
+	(egress_priority_map("egress-priority-map"): Vec<String>),
+	(flags("flags"): u32),
+	(id("id"): u32),
+	(ingress_priority_map("ingress-priority-map"): Vec<String>),
+	(parent("parent"): String),
+	(protocol("protocol"): String)
+);
+
+derive_value_build!(
+	WifiP2PSettings,
+	(peer("peer"): String),
+	(wfd_ies("wfd-ies"): Vec<u8>),
+	(wps_method("wps-method"): u32)
+);
+
 derive_value_build!(
 	Ipv4Settings,
 	(addresses("addresses"): Vec<Vec<u32>>),
@@ -218,7 +535,7 @@ derive_value_build!(
 	(ignore_auto_dns("ignore-auto-dns"): bool),
 	(ignore_auto_routes("ignore-auto-routes"): bool),
 	(may_fail("may-fail"): bool),
-	(method("method"): String),
+	(method("method"): Ipv4Method),
 	(never_default("never-default"): bool),
 	(ra_timeout("ra-timeout"): i32),
 	(route_metric("route-metric"): i32),
@@ -228,7 +545,7 @@ derive_value_build!(
 
 derive_value_build!(
 	Ipv6Settings,
-	(addr_gen_mode("addr-gen-mode"): i32),
+	(addr_gen_mode("addr-gen-mode"): Ipv6AddrGenMode),
 	(addresses("addresses"): Vec<String>),
 	(dad_timeout("dad-timeout"): i32),
 	(dhcp_duid("dhcp-duid"): Vec<u8>),
@@ -245,9 +562,9 @@ derive_value_build!(
 	(gateway("gateway"): String),
 	(ignore_auto_dns("ignore-auto-dns"): bool),
 	(ignore_auto_routes("ignore-auto-routes"): bool),
-	(ip6_privacy("ip6-privacy"): i32),
+	(ip6_privacy("ip6-privacy"): Ipv6Privacy),
 	(may_fail("may-fail"): bool),
-	(method("method"): String),
+	(method("method"): Ipv6Method),
 	(never_default("never-default"): bool),
 	(ra_timeout("ra-timeout"): i32),
 	(route_metric("route-metric"): i32),
@@ -259,7 +576,7 @@ derive_value_build!(
 derive_value_build!(
 	WwwProxySettings,
 	(browser_only("browser-only"): bool),
-	(method("method"): i32),
+	(method("method"): ProxyMethod),
 	(pac_script("pac-script"): String),
 	(pac_url("pac-url"): String)
 );
@@ -268,6 +585,8 @@ derive_value_build!(
 pub struct Secrets {
 	#[builder(setter(strip_option))]
 	pub wifi: Option<WifiSecurity>,
+	#[builder(setter(strip_option))]
+	pub ieee8021x: Option<Ieee8021xSettings>,
 }
 
 impl Secrets {
@@ -278,6 +597,11 @@ impl Secrets {
 				.await
 				.ok()
 				.and_then(|mut s| s.remove("802-11-wireless-security").map(WifiSecurity::new)),
+			ieee8021x: connection
+				.get_secrets("802-1x")
+				.await
+				.ok()
+				.and_then(|mut s| s.remove("802-1x").map(Ieee8021xSettings::new)),
 		}
 	}
 }
@@ -285,7 +609,7 @@ impl Secrets {
 derive_value_build!(
 	WifiSecurity,
 	(psk("psk"): String),
-	(key_mgmt("key-mgmt"): String),
+	(key_mgmt("key-mgmt"): KeyMgmt),
 	(auth_alg("auth-alg"): String),
 	(leap_password("leap-password"): String),
 	(leap_password_flags("leap-password-flags"): u32),
@@ -302,3 +626,44 @@ derive_value_build!(
 	(wep_tx_keyidx("wep-tx-keyidx"): u32),
 	(wps_method("wps-method"): u32)
 );
+
+derive_value_build!(
+	Ieee8021xSettings,
+	(altsubject_matches("altsubject-matches"): Vec<String>),
+	(anonymous_identity("anonymous-identity"): String),
+	(auth_timeout("auth-timeout"): i32),
+	(ca_cert("ca-cert"): CertificateSource),
+	(ca_cert_password("ca-cert-password"): String),
+	(ca_cert_password_flags("ca-cert-password-flags"): SecretFlags),
+	(ca_path("ca-path"): String),
+	(client_cert("client-cert"): CertificateSource),
+	(client_cert_password("client-cert-password"): String),
+	(client_cert_password_flags("client-cert-password-flags"): SecretFlags),
+	(domain_match("domain-match"): String),
+	(domain_suffix_match("domain-suffix-match"): String),
+	(eap("eap"): EapMethods),
+	(identity("identity"): String),
+	(optional("optional"): bool),
+	(pac_file("pac-file"): String),
+	(password("password"): String),
+	(password_flags("password-flags"): SecretFlags),
+	(phase1_fast_provisioning("phase1-fast-provisioning"): String),
+	(phase1_peaplabel("phase1-peaplabel"): String),
+	(phase1_peapver("phase1-peapver"): String),
+	(phase2_altsubject_matches("phase2-altsubject-matches"): Vec<String>),
+	(phase2_auth("phase2-auth"): Phase2Auth),
+	(phase2_autheap("phase2-autheap"): String),
+	(phase2_ca_cert("phase2-ca-cert"): CertificateSource),
+	(phase2_client_cert("phase2-client-cert"): CertificateSource),
+	(phase2_domain_suffix_match("phase2-domain-suffix-match"): String),
+	(phase2_private_key("phase2-private-key"): CertificateSource),
+	(phase2_private_key_password("phase2-private-key-password"): String),
+	(phase2_private_key_password_flags("phase2-private-key-password-flags"): SecretFlags),
+	(pin("pin"): String),
+	(pin_flags("pin-flags"): SecretFlags),
+	(private_key("private-key"): CertificateSource),
+	(private_key_password("private-key-password"): String),
+	(private_key_password_flags("private-key-password-flags"): SecretFlags),
+	(subject_match("subject-match"): String),
+	(system_ca_certs("system-ca-certs"): bool)
+);
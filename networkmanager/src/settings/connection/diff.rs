@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::Settings;
+use std::collections::BTreeSet;
+
+/// Properties of the `connection` setting that can be changed without
+/// reactivating the connection.
+const REAPPLICABLE_CONNECTION_PROPERTIES: &[&str] = &[
+	"id",
+	"autoconnect",
+	"autoconnect-priority",
+	"autoconnect-retries",
+	"zone",
+	"metered",
+	"lldp",
+	"mdns",
+	"llmnr",
+	"dns-over-tls",
+	"mptcp-flags",
+];
+
+/// Properties NetworkManager ignores when comparing settings, such as the
+/// `connection.timestamp` that is bumped on every activation.
+const IGNORED_PROPERTIES: &[(&str, &str)] = &[("connection", "timestamp")];
+
+/// Settings whose properties can all be changed without reactivating the connection.
+const REAPPLICABLE_SETTINGS: &[&str] = &["ipv4", "ipv6", "proxy", "link", "user"];
+
+/// A property that differs between two sets of settings.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SettingChange {
+	/// The setting name, such as `ipv4` or `802-3-ethernet`.
+	pub setting: String,
+	pub property: String,
+}
+
+impl SettingChange {
+	/// Whether the device can apply this change live through `Reapply`.
+	///
+	/// This is conservative: some device types accept further changes, which
+	/// are reported here as needing reactivation.
+	pub fn can_reapply(&self) -> bool {
+		match self.setting.as_str() {
+			"connection" => REAPPLICABLE_CONNECTION_PROPERTIES.contains(&self.property.as_str()),
+			"802-3-ethernet" => self.property == "mtu",
+			setting => REAPPLICABLE_SETTINGS.contains(&setting),
+		}
+	}
+}
+
+/// The properties that differ between two sets of settings.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SettingsDiff {
+	pub changes: Vec<SettingChange>,
+}
+
+impl SettingsDiff {
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty()
+	}
+
+	/// Whether every change can be applied live, or the connection has to be
+	/// reactivated for them to take effect.
+	pub fn can_reapply(&self) -> bool {
+		self.changes.iter().all(SettingChange::can_reapply)
+	}
+}
+
+impl Settings {
+	/// List the properties that differ between `self` and `other`, including
+	/// properties set in only one of them, but not those NetworkManager ignores
+	/// on `Reapply`.
+	pub fn diff(&self, other: &Settings) -> SettingsDiff {
+		let old = self.build();
+		let new = other.build();
+
+		let settings: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+		let mut changes = Vec::new();
+		for setting in settings {
+			let old = old.get(setting);
+			let new = new.get(setting);
+			let properties: BTreeSet<&String> = old
+				.into_iter()
+				.chain(new)
+				.flat_map(|properties| properties.keys())
+				.collect();
+			for property in properties {
+				let old = old.and_then(|properties| properties.get(property));
+				let new = new.and_then(|properties| properties.get(property));
+				let ignored = IGNORED_PROPERTIES.contains(&(setting.as_str(), property.as_str()));
+				if old != new && !ignored {
+					changes.push(SettingChange {
+						setting: setting.clone(),
+						property: property.clone(),
+					});
+				}
+			}
+		}
+		SettingsDiff { changes }
+	}
+}
@@ -0,0 +1,427 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use std::{
+	ffi::OsStr,
+	os::unix::ffi::OsStrExt,
+	path::{Path, PathBuf},
+};
+use zbus::zvariant::{OwnedValue, Value};
+
+/// Defines an enum for a string-typed setting property, keeping unrecognized
+/// values in an `Unknown` variant so that they round-trip unchanged.
+macro_rules! string_setting_enum {
+	($(#[$meta:meta])* $name:ident { $($variant:ident = $value:literal),* $(,)? }) => {
+		$(#[$meta])*
+		#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+		pub enum $name {
+			$($variant,)*
+			Unknown(String),
+		}
+
+		impl $name {
+			pub fn as_str(&self) -> &str {
+				match self {
+					$(Self::$variant => $value,)*
+					Self::Unknown(value) => value,
+				}
+			}
+
+			/// Decodes a value read from NetworkManager. This is deliberately not a
+			/// `From<&str>` impl, so that a misspelled value passed to a builder
+			/// setter fails to compile instead of becoming `Unknown`.
+			pub fn from_raw(value: &str) -> Self {
+				match value {
+					$($value => Self::$variant,)*
+					_ => Self::Unknown(value.to_owned()),
+				}
+			}
+		}
+
+		impl std::fmt::Display for $name {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				f.write_str(self.as_str())
+			}
+		}
+
+		impl TryFrom<OwnedValue> for $name {
+			type Error = zbus::zvariant::Error;
+
+			fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+				String::try_from(value).map(|value| Self::from_raw(&value))
+			}
+		}
+
+		impl From<$name> for Value<'_> {
+			fn from(value: $name) -> Self {
+				Value::from(value.as_str().to_owned())
+			}
+		}
+	};
+}
+
+/// Defines an enum for an integer-typed setting property, keeping unrecognized
+/// values in an `Unknown` variant so that they round-trip unchanged.
+macro_rules! int_setting_enum {
+	($(#[$meta:meta])* $name:ident: $repr:ty { $($variant:ident = $value:literal),* $(,)? }) => {
+		$(#[$meta])*
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		pub enum $name {
+			$($variant,)*
+			Unknown($repr),
+		}
+
+		impl From<$repr> for $name {
+			fn from(value: $repr) -> Self {
+				match value {
+					$($value => Self::$variant,)*
+					_ => Self::Unknown(value),
+				}
+			}
+		}
+
+		impl From<$name> for $repr {
+			fn from(value: $name) -> Self {
+				match value {
+					$($name::$variant => $value,)*
+					$name::Unknown(value) => value,
+				}
+			}
+		}
+
+		impl TryFrom<OwnedValue> for $name {
+			type Error = zbus::zvariant::Error;
+
+			fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+				<$repr>::try_from(value).map(Self::from)
+			}
+		}
+
+		impl From<$name> for Value<'_> {
+			fn from(value: $name) -> Self {
+				Value::from(<$repr>::from(value))
+			}
+		}
+	};
+}
+
+string_setting_enum!(
+	/// The `connection.type` property, naming the main setting of a profile.
+	ConnectionType {
+		Ethernet = "802-3-ethernet",
+		Wifi = "802-11-wireless",
+		Bluetooth = "bluetooth",
+		Bond = "bond",
+		Bridge = "bridge",
+		Cdma = "cdma",
+		Dummy = "dummy",
+		Generic = "generic",
+		Gsm = "gsm",
+		Loopback = "loopback",
+		Team = "team",
+		Tun = "tun",
+		Vlan = "vlan",
+		Vpn = "vpn",
+		WifiP2P = "wifi-p2p",
+		WireGuard = "wireguard",
+	}
+);
+
+string_setting_enum!(
+	/// The `802-11-wireless-security.key-mgmt` property.
+	KeyMgmt {
+		Wep = "none",
+		DynamicWep = "ieee8021x",
+		WpaPsk = "wpa-psk",
+		WpaEap = "wpa-eap",
+		WpaEapSuiteB192 = "wpa-eap-suite-b-192",
+		Sae = "sae",
+		Owe = "owe",
+	}
+);
+
+string_setting_enum!(
+	/// The `802-11-wireless.mode` property.
+	WifiConnectionMode {
+		Infrastructure = "infrastructure",
+		AdHoc = "adhoc",
+		Ap = "ap",
+		Mesh = "mesh",
+	}
+);
+
+string_setting_enum!(
+	/// The `ipv4.method` property.
+	Ipv4Method {
+		Auto = "auto",
+		Manual = "manual",
+		LinkLocal = "link-local",
+		Shared = "shared",
+		Disabled = "disabled",
+	}
+);
+
+string_setting_enum!(
+	/// The `ipv6.method` property.
+	Ipv6Method {
+		Auto = "auto",
+		Dhcp = "dhcp",
+		Manual = "manual",
+		LinkLocal = "link-local",
+		Shared = "shared",
+		Ignore = "ignore",
+		Disabled = "disabled",
+	}
+);
+
+int_setting_enum!(
+	/// The `connection.mdns` property.
+	MdnsMode: i32 {
+		Default = -1,
+		No = 0,
+		Resolve = 1,
+		Yes = 2,
+	}
+);
+
+int_setting_enum!(
+	/// The `connection.llmnr` property.
+	LlmnrMode: i32 {
+		Default = -1,
+		No = 0,
+		Resolve = 1,
+		Yes = 2,
+	}
+);
+
+int_setting_enum!(
+	/// The `connection.lldp` property.
+	LldpMode: i32 {
+		Default = -1,
+		Disable = 0,
+		EnableRx = 1,
+	}
+);
+
+int_setting_enum!(
+	/// The `connection.multi-connect` property.
+	MultiConnect: i32 {
+		Default = 0,
+		Single = 1,
+		ManualMultiple = 2,
+		Multiple = 3,
+	}
+);
+
+int_setting_enum!(
+	/// The `ipv6.addr-gen-mode` property.
+	Ipv6AddrGenMode: i32 {
+		Eui64 = 0,
+		StablePrivacy = 1,
+		DefaultOrEui64 = 2,
+		Default = 3,
+	}
+);
+
+int_setting_enum!(
+	/// The `ipv6.ip6-privacy` property.
+	Ipv6Privacy: i32 {
+		Default = -1,
+		Disabled = 0,
+		PreferPublicAddr = 1,
+		PreferTempAddr = 2,
+	}
+);
+
+int_setting_enum!(
+	/// The `proxy.method` property.
+	ProxyMethod: i32 {
+		None = 0,
+		Auto = 1,
+	}
+);
+
+int_setting_enum!(
+	/// The `connection.metered` property.
+	ConnectionMetered: i32 {
+		Default = 0,
+		Yes = 1,
+		No = 2,
+	}
+);
+
+string_setting_enum!(
+	/// An EAP method of the `802-1x.eap` property.
+	EapMethod {
+		Leap = "leap",
+		Md5 = "md5",
+		Tls = "tls",
+		Peap = "peap",
+		Ttls = "ttls",
+		Pwd = "pwd",
+		Fast = "fast",
+	}
+);
+
+/// The `802-1x.eap` property, listing the allowed EAP methods.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EapMethods(pub Vec<EapMethod>);
+
+impl TryFrom<OwnedValue> for EapMethods {
+	type Error = zbus::zvariant::Error;
+
+	fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+		let methods = Vec::<String>::try_from(value)?;
+		Ok(Self(
+			methods
+				.iter()
+				.map(|method| EapMethod::from_raw(method))
+				.collect(),
+		))
+	}
+}
+
+impl From<EapMethods> for Value<'_> {
+	fn from(value: EapMethods) -> Self {
+		let methods: Vec<String> = value
+			.0
+			.iter()
+			.map(|method| method.as_str().to_owned())
+			.collect();
+		Value::from(methods)
+	}
+}
+
+impl From<Vec<EapMethod>> for EapMethods {
+	fn from(methods: Vec<EapMethod>) -> Self {
+		Self(methods)
+	}
+}
+
+string_setting_enum!(
+	/// The `802-1x.phase2-auth` property, the inner authentication of tunneled EAP methods.
+	Phase2Auth {
+		Pap = "pap",
+		Chap = "chap",
+		Mschap = "mschap",
+		Mschapv2 = "mschapv2",
+		Gtc = "gtc",
+		Otp = "otp",
+		Md5 = "md5",
+		Tls = "tls",
+	}
+);
+
+bitflags::bitflags! {
+	/// How a secret such as a password is stored and requested.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub struct SecretFlags: u32 {
+		const AGENT_OWNED = 0x1;
+		const NOT_SAVED = 0x2;
+		const NOT_REQUIRED = 0x4;
+	}
+}
+
+impl TryFrom<OwnedValue> for SecretFlags {
+	type Error = zbus::zvariant::Error;
+
+	fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+		u32::try_from(value).map(SecretFlags::from_bits_retain)
+	}
+}
+
+impl From<SecretFlags> for Value<'_> {
+	fn from(value: SecretFlags) -> Self {
+		Value::from(value.bits())
+	}
+}
+
+/// A certificate or private key of an `802-1x` setting, referenced either by a
+/// file path or given inline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CertificateSource {
+	Path(PathBuf),
+	Blob(Vec<u8>),
+}
+
+impl CertificateSource {
+	const PATH_SCHEME: &'static [u8] = b"file://";
+
+	/// Decodes the byte array NetworkManager uses for certificate properties.
+	pub fn from_bytes(bytes: Vec<u8>) -> Self {
+		match bytes.strip_prefix(Self::PATH_SCHEME) {
+			Some(path) => {
+				let path = path.strip_suffix(b"\0").unwrap_or(path);
+				Self::Path(PathBuf::from(OsStr::from_bytes(path)))
+			}
+			None => Self::Blob(bytes),
+		}
+	}
+
+	/// Encodes the certificate as NetworkManager expects it: paths become a
+	/// NUL-terminated `file://` URI, blobs are passed as-is.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		match self {
+			Self::Path(path) => {
+				let path = path.as_os_str().as_bytes();
+				let mut bytes = Vec::with_capacity(Self::PATH_SCHEME.len() + path.len() + 1);
+				bytes.extend_from_slice(Self::PATH_SCHEME);
+				bytes.extend_from_slice(path);
+				bytes.push(0);
+				bytes
+			}
+			Self::Blob(blob) => blob.clone(),
+		}
+	}
+}
+
+impl From<PathBuf> for CertificateSource {
+	fn from(path: PathBuf) -> Self {
+		Self::Path(path)
+	}
+}
+
+impl From<&Path> for CertificateSource {
+	fn from(path: &Path) -> Self {
+		Self::Path(path.to_owned())
+	}
+}
+
+impl TryFrom<OwnedValue> for CertificateSource {
+	type Error = zbus::zvariant::Error;
+
+	fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+		Vec::<u8>::try_from(value).map(Self::from_bytes)
+	}
+}
+
+impl From<CertificateSource> for Value<'_> {
+	fn from(value: CertificateSource) -> Self {
+		Value::from(value.to_bytes())
+	}
+}
+
+string_setting_enum!(
+	/// The `connection.port-type` (formerly `slave-type`) property, naming the
+	/// type of the controller a port profile is attached to.
+	PortType {
+		Bond = "bond",
+		Bridge = "bridge",
+		Team = "team",
+		OvsBridge = "ovs-bridge",
+		OvsPort = "ovs-port",
+		Vrf = "vrf",
+	}
+);
+
+string_setting_enum!(
+	/// The `mode` option of a bond.
+	BondMode {
+		BalanceRr = "balance-rr",
+		ActiveBackup = "active-backup",
+		BalanceXor = "balance-xor",
+		Broadcast = "broadcast",
+		Lacp = "802.3ad",
+		BalanceTlb = "balance-tlb",
+		BalanceAlb = "balance-alb",
+	}
+);
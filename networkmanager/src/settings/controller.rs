@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{
+	NetworkManagerSettings,
+	connection::{
+		Connection, ConnectionSettings, EthernetSettings, Settings,
+		enums::{ConnectionType, PortType},
+	},
+};
+use crate::util::generate_uuid;
+use zbus::Result;
+
+/// Builds a bond, bridge or team profile together with the profiles of its ports.
+///
+/// Ports are attached through `connection.master` and `connection.slave-type`,
+/// which every NetworkManager version accepts, rather than their newer
+/// `controller` and `port-type` aliases.
+#[derive(Debug, Clone)]
+pub struct ControllerProfileBuilder {
+	controller: Settings,
+	ports: Vec<Settings>,
+}
+
+impl ControllerProfileBuilder {
+	pub fn new(controller: Settings) -> Self {
+		Self {
+			controller,
+			ports: Vec::new(),
+		}
+	}
+
+	/// Add a port profile. Its controller and port type are filled in by [`Self::build`].
+	pub fn port(mut self, port: Settings) -> Self {
+		self.ports.push(port);
+		self
+	}
+
+	/// Add an Ethernet port profile bound to the given interface.
+	pub fn ethernet_port(self, interface_name: impl Into<String>) -> Self {
+		let interface_name = interface_name.into();
+		let controller_id = self
+			.controller
+			.connection
+			.as_ref()
+			.and_then(|connection| connection.id.as_deref())
+			.unwrap_or_default();
+		let port = Settings {
+			connection: Some(ConnectionSettings {
+				id: Some(format!("{controller_id} port {interface_name}")),
+				interface_name: Some(interface_name),
+				type_: Some(ConnectionType::Ethernet),
+				autoconnect: Some(true),
+				..Default::default()
+			}),
+			ethernet: Some(EthernetSettings::default()),
+			..Default::default()
+		};
+		self.port(port)
+	}
+
+	/// Resolve the controller/port relationship, assigning the controller a UUID
+	/// if it has none, and return the controller profile followed by its ports.
+	pub fn build(&self) -> Result<(Settings, Vec<Settings>)> {
+		let mut controller = self.controller.clone();
+		let connection = controller.connection.get_or_insert_with(Default::default);
+		let port_type = match &connection.type_ {
+			Some(ConnectionType::Bond) => PortType::Bond,
+			Some(ConnectionType::Bridge) => PortType::Bridge,
+			Some(ConnectionType::Team) => PortType::Team,
+			other => {
+				return Err(zbus::Error::Failure(format!(
+					"a connection of type {other:?} cannot have ports"
+				)));
+			}
+		};
+		let uuid = match &connection.uuid {
+			Some(uuid) => uuid.clone(),
+			None => connection.uuid.insert(generate_uuid()?).clone(),
+		};
+
+		let ports = self
+			.ports
+			.iter()
+			.cloned()
+			.map(|mut port| {
+				let connection = port.connection.get_or_insert_with(Default::default);
+				connection.master = Some(uuid.clone());
+				connection.slave_type = Some(port_type.clone());
+				port
+			})
+			.collect();
+		Ok((controller, ports))
+	}
+
+	/// Add the controller and port profiles. If adding any of them fails, the
+	/// profiles added so far are deleted again so no partial setup is left behind.
+	pub async fn add<'a>(
+		&self,
+		settings: &'a NetworkManagerSettings<'a>,
+	) -> Result<(Connection<'a>, Vec<Connection<'a>>)> {
+		let (controller, ports) = self.build()?;
+		let controller = settings.add_connection(&controller).await?;
+
+		let mut added = Vec::with_capacity(ports.len());
+		for port in &ports {
+			match settings.add_connection(port).await {
+				Ok(port) => added.push(port),
+				Err(why) => {
+					for connection in added.iter().chain(std::iter::once(&controller)) {
+						let _ = connection.delete().await;
+					}
+					return Err(why);
+				}
+			}
+		}
+		Ok((controller, added))
+	}
+}
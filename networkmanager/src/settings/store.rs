@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::connection::Connection;
+use crate::{
+	interface::{
+		enums::ConnectionFlags,
+		settings::{SettingsProxy, connection::ConnectionSettingsProxy},
+	},
+	util::{build_proxies, get_all_properties, take_property},
+};
+use std::path::{Path, PathBuf};
+use zbus::Result;
+
+/// Connection profiles as they are stored on disk.
+#[derive(Debug)]
+pub struct ProfileStore<'a>(SettingsProxy<'a>);
+
+impl<'a> ProfileStore<'a> {
+	pub async fn new(connection: &'a zbus::Connection) -> Result<ProfileStore<'a>> {
+		SettingsProxy::new(connection).await.map(Self)
+	}
+
+	/// Every connection profile with the file backing it and its storage flags.
+	pub async fn profiles(&self) -> Result<Vec<StoredProfile<'a>>> {
+		let connections = self.0.list_connections().await?;
+		let proxies: Vec<ConnectionSettingsProxy> =
+			build_proxies(self.0.inner().connection(), connections).await?;
+		futures_util::future::try_join_all(proxies.into_iter().map(StoredProfile::new)).await
+	}
+
+	/// Load or reload the given files, adding new profiles and updating changed
+	/// ones. Files that fail to load are reported in [`LoadedFiles::failed`].
+	pub async fn load(&self, filenames: &[impl AsRef<Path>]) -> Result<LoadedFiles> {
+		let filenames: Vec<&str> = filenames
+			.iter()
+			.map(|filename| {
+				filename.as_ref().to_str().ok_or_else(|| {
+					zbus::Error::Failure(format!(
+						"connection file path is not valid UTF-8: {}",
+						filename.as_ref().display()
+					))
+				})
+			})
+			.collect::<Result<_>>()?;
+		let (_, failures) = self.0.load_connections(&filenames).await?;
+		let (failed, loaded) = filenames
+			.into_iter()
+			.map(PathBuf::from)
+			.partition(|filename| {
+				failures
+					.iter()
+					.any(|failure| Path::new(failure) == filename)
+			});
+		Ok(LoadedFiles { loaded, failed })
+	}
+
+	/// Reload every connection file from disk.
+	pub async fn reload_all(&self) -> Result<()> {
+		self.0.reload_connections().await.map(|_| ())
+	}
+
+	/// Write every profile which exists only in memory to disk, returning the
+	/// profiles that were saved. Profiles generated by NetworkManager, volatile
+	/// profiles and profiles mirroring external configuration are skipped, as
+	/// they are not meant to outlive the runtime state they describe.
+	pub async fn persist_unsaved(&self) -> Result<Vec<StoredProfile<'a>>> {
+		let transient =
+			ConnectionFlags::NM_GENERATED | ConnectionFlags::VOLATILE | ConnectionFlags::EXTERNAL;
+		let unsaved: Vec<_> = self
+			.profiles()
+			.await?
+			.into_iter()
+			.filter(|profile| profile.is_unsaved() && !profile.flags.intersects(transient))
+			.collect();
+		futures_util::future::try_join_all(unsaved.iter().map(StoredProfile::persist)).await?;
+		Ok(unsaved)
+	}
+}
+
+/// A connection profile along with how it is stored.
+#[derive(Debug)]
+pub struct StoredProfile<'a> {
+	pub connection: Connection<'a>,
+	/// The file the profile is stored in, if any.
+	pub filename: Option<PathBuf>,
+	pub flags: ConnectionFlags,
+}
+
+impl<'a> StoredProfile<'a> {
+	async fn new(proxy: ConnectionSettingsProxy<'a>) -> Result<Self> {
+		let mut properties = get_all_properties(proxy.inner()).await?;
+		let filename = take_property::<String>(&mut properties, "Filename")
+			.filter(|filename| !filename.is_empty())
+			.map(PathBuf::from);
+		let mut flags = take_property(&mut properties, "Flags")
+			.map(ConnectionFlags::from_bits_truncate)
+			.unwrap_or_else(ConnectionFlags::empty);
+		if take_property(&mut properties, "Unsaved").unwrap_or(false) {
+			flags |= ConnectionFlags::UNSAVED;
+		}
+		Ok(Self {
+			connection: proxy.into(),
+			filename,
+			flags,
+		})
+	}
+
+	/// Whether the profile has changes that are not yet written to disk.
+	pub fn is_unsaved(&self) -> bool {
+		self.flags.contains(ConnectionFlags::UNSAVED)
+	}
+
+	/// Whether the profile is deleted once it is no longer active.
+	pub fn is_volatile(&self) -> bool {
+		self.flags.contains(ConnectionFlags::VOLATILE)
+	}
+
+	/// Whether the profile mirrors a configuration made outside NetworkManager.
+	pub fn is_external(&self) -> bool {
+		self.flags.contains(ConnectionFlags::EXTERNAL)
+	}
+
+	/// Write the profile to disk.
+	pub async fn persist(&self) -> Result<()> {
+		self.connection.save().await
+	}
+}
+
+/// The outcome of [`ProfileStore::load`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadedFiles {
+	pub loaded: Vec<PathBuf>,
+	pub failed: Vec<PathBuf>,
+}
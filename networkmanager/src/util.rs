@@ -1,6 +1,11 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use jiff::Timestamp;
+use std::collections::HashMap;
+use zbus::{
+	names::{BusName, InterfaceName},
+	zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value},
+};
 
 pub fn clock_boottime_to_time(time: i32) -> Option<Timestamp> {
 	let boot_time = procfs::boot_time_secs()
@@ -8,3 +13,149 @@ pub fn clock_boottime_to_time(time: i32) -> Option<Timestamp> {
 		.and_then(|boot_time| i64::try_from(boot_time).ok())?;
 	Timestamp::from_second(boot_time + time as i64).ok()
 }
+
+/// Fetches every property of the proxy's interface in a single `GetAll` call.
+pub async fn get_all_properties(
+	proxy: &zbus::Proxy<'_>,
+) -> zbus::Result<HashMap<String, OwnedValue>> {
+	get_all_properties_from(
+		proxy.connection(),
+		proxy.destination().to_owned(),
+		proxy.path(),
+		proxy.interface().clone(),
+	)
+	.await
+}
+
+/// Fetches every property of a NetworkManager object's interface in a single `GetAll` call.
+pub async fn get_all_properties_at(
+	connection: &zbus::Connection,
+	path: &ObjectPath<'_>,
+	interface: InterfaceName<'_>,
+) -> zbus::Result<HashMap<String, OwnedValue>> {
+	let destination = BusName::from_static_str("org.freedesktop.NetworkManager")?;
+	get_all_properties_from(connection, destination, path, interface).await
+}
+
+async fn get_all_properties_from(
+	connection: &zbus::Connection,
+	destination: BusName<'_>,
+	path: &ObjectPath<'_>,
+	interface: InterfaceName<'_>,
+) -> zbus::Result<HashMap<String, OwnedValue>> {
+	let properties = zbus::fdo::PropertiesProxy::builder(connection)
+		.destination(destination)?
+		.path(path.to_owned())?
+		.cache_properties(zbus::proxy::CacheProperties::No)
+		.build()
+		.await?;
+	Ok(properties.get_all(interface).await?)
+}
+
+/// Whether the error comes from calling an object that no longer exists, as
+/// happens when it is removed between listing its path and calling it.
+pub fn is_vanished(error: &zbus::Error) -> bool {
+	match error {
+		zbus::Error::FDO(error) => matches!(
+			**error,
+			zbus::fdo::Error::UnknownObject(_)
+				| zbus::fdo::Error::UnknownInterface(_)
+				| zbus::fdo::Error::UnknownMethod(_)
+		),
+		zbus::Error::MethodError(name, _, _) => matches!(
+			name.as_str(),
+			"org.freedesktop.DBus.Error.UnknownObject"
+				| "org.freedesktop.DBus.Error.UnknownInterface"
+				| "org.freedesktop.DBus.Error.UnknownMethod"
+		),
+		_ => false,
+	}
+}
+
+/// Awaits a future per object concurrently, leaving out the objects that
+/// vanished in the meantime rather than failing as a whole.
+pub async fn join_existing<T>(
+	futures: impl IntoIterator<Item = impl Future<Output = zbus::Result<T>>>,
+) -> zbus::Result<Vec<T>> {
+	let mut values = Vec::new();
+	for result in futures_util::future::join_all(futures).await {
+		match result {
+			Ok(value) => values.push(value),
+			Err(why) if is_vanished(&why) => (),
+			Err(why) => return Err(why),
+		}
+	}
+	Ok(values)
+}
+
+/// Builds a proxy for each object path concurrently, rather than one after another.
+pub async fn build_proxies<'a, P>(
+	connection: &zbus::Connection,
+	paths: Vec<OwnedObjectPath>,
+) -> zbus::Result<Vec<P>>
+where
+	P: zbus::proxy::Defaults + From<zbus::Proxy<'a>>,
+{
+	futures_util::future::try_join_all(paths.into_iter().map(|path| async move {
+		zbus::proxy::Builder::<P>::new(connection)
+			.path(path)?
+			.build()
+			.await
+	}))
+	.await
+}
+
+/// Takes a property out of a `GetAll` result, converting it to `T`.
+pub fn take_property<T: TryFrom<OwnedValue>>(
+	properties: &mut HashMap<String, OwnedValue>,
+	name: &str,
+) -> Option<T> {
+	properties
+		.remove(name)
+		.and_then(|value| value.try_into().ok())
+}
+
+/// Generates a random UUID for a new connection profile.
+pub fn generate_uuid() -> std::io::Result<String> {
+	std::fs::read_to_string("/proc/sys/kernel/random/uuid").map(|uuid| uuid.trim().to_owned())
+}
+
+/// Parses a colon-separated hardware address such as `AA:BB:CC:DD:EE:FF`.
+pub fn parse_hw_address(address: &str) -> Option<Vec<u8>> {
+	address
+		.split(':')
+		.map(|octet| u8::from_str_radix(octet, 16).ok())
+		.collect()
+}
+
+/// Converts settings as returned by `GetSettings` into values that can be sent back.
+pub fn owned_settings_to_values(
+	settings: HashMap<String, HashMap<String, OwnedValue>>,
+) -> HashMap<String, HashMap<String, Value<'static>>> {
+	settings
+		.into_iter()
+		.map(|(name, section)| {
+			let section = section
+				.into_iter()
+				.map(|(key, value)| (key, Value::from(value)))
+				.collect();
+			(name, section)
+		})
+		.collect()
+}
+
+/// Borrows owned settings in the form expected by the settings proxy methods.
+pub fn settings_ref<'a>(
+	settings: &'a HashMap<String, HashMap<String, Value<'_>>>,
+) -> HashMap<&'a str, HashMap<&'a str, Value<'a>>> {
+	settings
+		.iter()
+		.map(|(name, section)| {
+			let section = section
+				.iter()
+				.map(|(key, value)| (key.as_str(), value.clone()))
+				.collect();
+			(name.as_str(), section)
+		})
+		.collect()
+}
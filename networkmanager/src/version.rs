@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use std::{fmt, str::FromStr};
+
+/// A NetworkManager daemon version, as reported by its `Version` property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+	pub major: u32,
+	pub minor: u32,
+	pub micro: u32,
+}
+
+impl Version {
+	pub const fn new(major: u32, minor: u32, micro: u32) -> Self {
+		Self {
+			major,
+			minor,
+			micro,
+		}
+	}
+
+	pub fn at_least(self, major: u32, minor: u32) -> bool {
+		self >= Self::new(major, minor, 0)
+	}
+
+	/// Whether `Settings.Connection.Update2` is available.
+	pub fn supports_update2(self) -> bool {
+		self.at_least(1, 12)
+	}
+
+	/// Whether `AddAndActivateConnection2` is available.
+	pub fn supports_add_and_activate_connection2(self) -> bool {
+		self.at_least(1, 16)
+	}
+
+	/// Whether `Settings.AddConnection2` is available.
+	pub fn supports_add_connection2(self) -> bool {
+		self.at_least(1, 20)
+	}
+
+	/// Whether the `Reload` method is available.
+	pub fn supports_reload(self) -> bool {
+		self.at_least(1, 22)
+	}
+
+	/// Whether profiles accept `connection.controller` and `connection.port-type`
+	/// in place of `master` and `slave-type`.
+	pub fn supports_controller_properties(self) -> bool {
+		self.at_least(1, 46)
+	}
+}
+
+impl fmt::Display for Version {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}.{}.{}", self.major, self.minor, self.micro)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseVersionError(String);
+
+impl fmt::Display for ParseVersionError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "invalid NetworkManager version: {:?}", self.0)
+	}
+}
+
+impl std::error::Error for ParseVersionError {}
+
+impl FromStr for Version {
+	type Err = ParseVersionError;
+
+	/// Parses versions such as `1.46.0` or `1.47.2-dev`, ignoring any suffix
+	/// after the numeric components.
+	fn from_str(version: &str) -> Result<Self, Self::Err> {
+		let numeric = version
+			.split(|c: char| !c.is_ascii_digit() && c != '.')
+			.next()
+			.unwrap_or_default();
+		let mut parts = numeric.split('.').map(u32::from_str);
+		let mut next = || parts.next().transpose().ok().flatten();
+		match (next(), next(), next()) {
+			(Some(major), Some(minor), micro) => Ok(Self::new(major, minor, micro.unwrap_or(0))),
+			_ => Err(ParseVersionError(version.to_owned())),
+		}
+	}
+}
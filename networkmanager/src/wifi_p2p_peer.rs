@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+	access_point::signal_bars,
+	interface::{enums::ApFlags, wifi_p2p_peer::WifiP2PPeerProxy},
+	util::{clock_boottime_to_time, get_all_properties, get_all_properties_at, take_property},
+};
+use std::{collections::HashMap, ops::Deref};
+use zbus::{
+	Result,
+	names::InterfaceName,
+	zvariant::{ObjectPath, OwnedValue},
+};
+
+#[derive(Debug)]
+pub struct WifiP2PPeer<'a>(WifiP2PPeerProxy<'a>);
+
+impl<'a> WifiP2PPeer<'a> {
+	/// Fetch all properties of the peer in one round-trip.
+	pub async fn info(&self) -> Result<WifiP2PPeerInfo> {
+		get_all_properties(self.0.inner())
+			.await
+			.map(WifiP2PPeerInfo::new)
+	}
+
+	pub async fn flags(&self) -> Result<ApFlags> {
+		self.0.flags().await.map(ApFlags::from_bits_truncate)
+	}
+
+	pub async fn last_seen(&self) -> Result<Option<jiff::Timestamp>> {
+		Ok(clock_boottime_to_time(self.0.last_seen().await?))
+	}
+}
+
+impl<'a> Deref for WifiP2PPeer<'a> {
+	type Target = WifiP2PPeerProxy<'a>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<'a> From<WifiP2PPeerProxy<'a>> for WifiP2PPeer<'a> {
+	fn from(peer: WifiP2PPeerProxy<'a>) -> Self {
+		WifiP2PPeer(peer)
+	}
+}
+
+/// A decoded view of all properties of a Wi-Fi P2P peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifiP2PPeerInfo {
+	pub name: String,
+	pub manufacturer: String,
+	pub model: String,
+	pub model_number: String,
+	pub serial: String,
+	pub hw_address: String,
+	/// The Wi-Fi Display information elements the peer advertises, which
+	/// identify Miracast sinks and sources.
+	pub wfd_ies: Vec<u8>,
+	/// The signal quality in percent.
+	pub strength: u8,
+	/// The signal quality as 0 to 4 bars, see [`signal_bars`].
+	pub bars: u8,
+	pub flags: ApFlags,
+	pub last_seen: Option<jiff::Timestamp>,
+}
+
+impl WifiP2PPeerInfo {
+	/// Decodes the result of `GetAll` on a peer.
+	pub fn new(mut properties: HashMap<String, OwnedValue>) -> Self {
+		let strength = take_property(&mut properties, "Strength").unwrap_or_default();
+		WifiP2PPeerInfo {
+			name: take_property(&mut properties, "Name").unwrap_or_default(),
+			manufacturer: take_property(&mut properties, "Manufacturer").unwrap_or_default(),
+			model: take_property(&mut properties, "Model").unwrap_or_default(),
+			model_number: take_property(&mut properties, "ModelNumber").unwrap_or_default(),
+			serial: take_property(&mut properties, "Serial").unwrap_or_default(),
+			hw_address: take_property(&mut properties, "HwAddress").unwrap_or_default(),
+			wfd_ies: take_property(&mut properties, "WfdIEs").unwrap_or_default(),
+			strength,
+			bars: signal_bars(strength),
+			flags: take_property(&mut properties, "Flags")
+				.map(ApFlags::from_bits_truncate)
+				.unwrap_or_else(ApFlags::empty),
+			last_seen: take_property(&mut properties, "LastSeen").and_then(clock_boottime_to_time),
+		}
+	}
+
+	/// Fetch the properties of the peer at `path` without building a proxy for it.
+	pub async fn fetch(connection: &zbus::Connection, path: &ObjectPath<'_>) -> Result<Self> {
+		let interface =
+			InterfaceName::from_static_str_unchecked("org.freedesktop.NetworkManager.WifiP2PPeer");
+		get_all_properties_at(connection, path, interface)
+			.await
+			.map(WifiP2PPeerInfo::new)
+	}
+}